@@ -6,4 +6,6 @@ rm -f ./compiler_output/*.ll
 rm -f ./compiler_output/*.asm
 rm -f ./compiler_output/*.o
 rm -f ./compiler_output/*.out
+rm -f ./compiler_output/*.a
+rm -f ./compiler_output/*.so
 
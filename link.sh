@@ -1,3 +0,0 @@
-#!/bin/sh
-
-ld ./compiler_output/*.o -o ./compiler_output/a.out
@@ -12,6 +12,40 @@ pub struct Parameters {
     pub generate_ir: bool,
     pub generate_asm: bool,
     pub generate_object: bool,
+    pub print_type_sizes: Option<TypeSizesFormat>,
+    pub hosted: bool,
+    /// The path of the final artifact set with `-o`
+    pub output: Option<String>,
+    pub emit: Option<Emit>,
+    pub crate_type: CrateType,
+    /// Directories that the linker searches for libraries (`-L`)
+    pub library_paths: Vec<String>,
+    /// Libraries that the program is linked against (`-l`)
+    pub libraries: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TypeSizesFormat {
+    Human,
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Emit {
+    /// Links the object files into an executable
+    Exe,
+    /// Only generates object files
+    Obj,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CrateType {
+    /// An executable
+    Bin,
+    /// A `.a` archive of the object files
+    Staticlib,
+    /// A shared object
+    Cdylib,
 }
 
 argtea_impl! {
@@ -23,13 +57,55 @@ argtea_impl! {
             std::process::exit(0);
         }
 
-        /// Sets the output directory to write the generated files to.
-        ("--output-directory" | "--output-dir" | "--output" | "-o", output_dir) => {
+        /// Sets the output directory to write the generated files to. This is no longer set by
+        /// `-o`/`--output`.
+        ("--output-directory" | "--output-dir", output_dir) => {
             out_dir = Cow::Owned(
                 output_dir.ok_or("expected output directory")?
             );
         }
 
+        /// Sets the path of the final executable or library (not the output directory). By
+        /// default, it is written to the output directory as `a.out`, `liba.a` or `liba.so`.
+        ("--output" | "-o", path) => {
+            output = Some(path.ok_or("expected output path")?);
+        }
+
+        /// Sets what to emit: `exe` links the object files into an executable (default if
+        /// there is a `main` function) and `obj` only generates the object files.
+        ("--emit", kind) => {
+            let kind = kind.ok_or("expected kind to emit")?;
+
+            emit = match &*kind {
+                "exe" => Some(Emit::Exe),
+                "obj" => Some(Emit::Obj),
+                other => return Err(format!("invalid kind to emit `{other}`").into()),
+            }
+        }
+
+        /// Sets the kind of artifact to produce: `bin` (default), `staticlib` (a `.a`
+        /// archive) or `cdylib` (a shared object).
+        ("--crate-type", type_) => {
+            let type_ = type_.ok_or("expected crate type")?;
+
+            crate_type = match &*type_ {
+                "bin" => CrateType::Bin,
+                "staticlib" => CrateType::Staticlib,
+                "cdylib" => CrateType::Cdylib,
+                other => return Err(format!("invalid crate type `{other}`").into()),
+            }
+        }
+
+        /// Adds a directory to the library search path of the linker.
+        ("--library-path" | "-L", path) => {
+            library_paths.push(path.ok_or("expected library path")?);
+        }
+
+        /// Links against a library (eg `-l m` for `libm`).
+        ("--library" | "-l", name) => {
+            libraries.push(name.ok_or("expected library name")?);
+        }
+
         /// Disables all compiler optimization
         #[fake]
         ("-O0") => {}
@@ -64,7 +140,9 @@ argtea_impl! {
         /// Unlike the other options, the generated file will be of the form
         /// `file_name.lex` where `file_name` is the input file name with the `.wlang`
         /// extention removed if present.
-        ("--lex" | "-l") => {
+        ///
+        /// `-l` no longer aliases this option; it now links against a library.
+        ("--lex") => {
             lex_files = true;
         }
 
@@ -118,6 +196,27 @@ argtea_impl! {
             generate_object = false;
         }
 
+        /// Prints the size, alignment and field layout of every struct to stdout.
+        ("--print-type-sizes") => {
+            print_type_sizes = Some(TypeSizesFormat::Human);
+        }
+
+        /// Same as `--print-type-sizes` but prints the layouts as JSON.
+        ("--print-type-sizes-json") => {
+            print_type_sizes = Some(TypeSizesFormat::Json);
+        }
+
+        /// Generates a program that is linked against libc. The entrypoint becomes the C `main`
+        /// function and `std` uses libc instead of raw system calls.
+        ("--hosted") => {
+            hosted = true;
+        }
+
+        /// Generates a freestanding program with its own `_start` entrypoint (default).
+        ("--freestanding") => {
+            hosted = false;
+        }
+
         /// Input files
         (input_file) => {
             if input_file.starts_with("-") {
@@ -146,6 +245,13 @@ argtea_impl! {
             let mut generate_ir: bool = false;
             let mut generate_asm: bool = false;
             let mut generate_object: bool = true;
+            let mut print_type_sizes: Option<TypeSizesFormat> = None;
+            let mut hosted: bool = false;
+            let mut output: Option<String> = None;
+            let mut emit: Option<Emit> = None;
+            let mut crate_type: CrateType = CrateType::Bin;
+            let mut library_paths: Vec<String> = Vec::new();
+            let mut libraries: Vec<String> = Vec::new();
 
             parse!(std::env::args().skip(1).take_while(|a| a != "--"));
 
@@ -157,7 +263,7 @@ argtea_impl! {
                 input_files.push(file);
             };
 
-            return Ok(Self { input_files, out_dir, opt_level, lex_files, generate_ast, generate_ir, generate_asm, generate_object });
+            return Ok(Self { input_files, out_dir, opt_level, lex_files, generate_ast, generate_ir, generate_asm, generate_object, print_type_sizes, hosted, output, emit, crate_type, library_paths, libraries });
         }
     }
 }
@@ -9,6 +9,7 @@ mod codegen_unit;
 
 pub use codegen_context::CodegenContext;
 
+mod const_value;
 mod error;
 mod intrinsics;
 mod namestore;
@@ -22,8 +23,11 @@ use codegen_unit::CodegenUnit;
 struct CoreTypes<'ctx> {
     unit: StructType<'ctx>,
     bool: IntType<'ctx>,
+    char: IntType<'ctx>,
     isize: IntType<'ctx>,
     str: StructType<'ctx>,
+    /// Slices (`[T]`) are represented the same way as `str`: a pointer and a length
+    slice: StructType<'ctx>,
 }
 
 impl<'ctx> CoreTypes<'ctx> {
@@ -34,8 +38,10 @@ impl<'ctx> CoreTypes<'ctx> {
         Self {
             unit: context.struct_type(&[], false),
             bool,
+            char: context.int_type(32),
             isize,
             str: context.struct_type(&[*context.ptr_type(), *isize], false),
+            slice: context.struct_type(&[*context.ptr_type(), *isize], false),
         }
     }
 }
@@ -1,7 +1,7 @@
-use std::{ffi::CString, io::Write as _, path::Path};
+use std::{collections::HashMap, ffi::CString, io::Write as _, path::Path};
 
 use wllvm::{
-    target::{self, Target, TargetData, TargetMachine},
+    target::{self, RelocMode, Target, TargetData, TargetMachine},
     Context, Module as LlvmModule,
 };
 
@@ -10,22 +10,27 @@ use crate::{
     codegen::{
         self,
         codegen_unit::CodegenUnit,
-        namestore::{NameStore, StructInfo},
+        namestore::{NameStore, StructInfo, TypeAliasInfo},
         scope::Scope,
+        types::Type,
         CoreTypes,
     },
-    error_handling::Diagnostic,
+    error_handling::{Diagnostic, Spanned as S},
     parser::ast::{self},
     util::PushVec,
 };
 
+pub(super) mod abi;
 mod functions;
 mod structs;
+mod type_sizes;
 
 pub struct Crate<'ctx> {
     pub llvm_module: LlvmModule<'ctx>,
     pub name: String,
     pub file_no: usize,
+    /// The libraries requested with `#![link(name = "...")]`
+    pub libraries: Vec<String>,
 }
 
 pub struct CodegenContext<'ctx> {
@@ -38,6 +43,8 @@ pub struct CodegenContext<'ctx> {
     pub(super) params: &'ctx cmdline::Parameters,
     /// The crate that contains the `main` function
     pub(super) main_crate: Option<String>,
+    /// The crates that replace the default global allocator, by function kind (eg `alloc`)
+    pub(super) global_allocators: HashMap<String, String>,
     pub warnings: PushVec<(usize, Diagnostic)>,
 }
 
@@ -49,13 +56,21 @@ impl<'ctx> CodegenContext<'ctx> {
 
         let target_triple = target::host_target_triple();
 
+        // Hosted programs are linked by the C compiler, which produces position-independent
+        // executables by default. Shared objects always have to be position-independent
+        let reloc_mode = if params.hosted || params.crate_type == cmdline::CrateType::Cdylib {
+            RelocMode::PIC
+        } else {
+            RelocMode::default()
+        };
+
         let target = Target::from_triple(&target_triple).unwrap();
         let target = target.create_target_machine(
             &target_triple,
             &target::host_cpu(),
             &target::host_cpu_features(),
             params.opt_level,
-            Default::default(),
+            reloc_mode,
             Default::default(),
         );
 
@@ -75,12 +90,18 @@ impl<'ctx> CodegenContext<'ctx> {
             files,
             params,
             main_crate: None,
+            global_allocators: HashMap::new(),
             warnings,
         }
     }
 }
 
 impl<'ctx> CodegenContext<'ctx> {
+    /// Checks if any crate contains a `main` function
+    pub fn has_main(&self) -> bool {
+        self.main_crate.is_some()
+    }
+
     pub fn create_crate(
         &mut self,
         ast: &ast::Module,
@@ -90,9 +111,12 @@ impl<'ctx> CodegenContext<'ctx> {
         let file_no = self.files.len() - 1;
 
         let mut crate_name = None;
+        let mut libraries = Vec::new();
+
         for attr in &ast.attributes {
             match **attr {
                 crate::parser::ast::Attribute::DeclareCrate(name) => crate_name = Some(name),
+                crate::parser::ast::Attribute::Link(ref name) => libraries.push(name.clone()),
                 _ => return Err(codegen::error::non_module_attribute(attr)),
             }
         }
@@ -115,29 +139,69 @@ impl<'ctx> CodegenContext<'ctx> {
                     llvm_type: Some(llvm_type),
                     fields: Vec::new(),
                     packed: false,
+                    repr_c: false,
+                    explicit_align: None,
+                    alignment: 1,
                     line_no: 0,
                     file_no: 0,
+                    visibility: struct_.visibility,
+                    drop_fn: None,
+                    copy: false,
                 },
             );
         }
 
+        for alias in &ast.type_aliases {
+            let target = alias
+                .type_
+                .iter()
+                .map(|segment| S(segment.0.to_owned(), segment.1))
+                .collect();
+
+            let added = self.name_store.add_type_alias(
+                &[crate_name, *alias.name],
+                TypeAliasInfo {
+                    target: S(target, alias.type_.1),
+                    crate_name: crate_name.to_owned(),
+                    visibility: alias.visibility,
+                },
+            );
+
+            if !added {
+                return Err(codegen::error::item_already_defined(alias.name));
+            }
+        }
+
         Ok(Crate {
             llvm_module: module,
             name: crate_name.into(),
             file_no,
+            libraries,
         })
     }
 
-    pub fn add_types_and_functions(
+    pub fn add_types(
         &mut self,
         ast: &ast::Module,
         source: &str,
         crate_: &Crate<'ctx>,
     ) -> Result<(), Diagnostic> {
-        self.generate_struct_bodies(ast, source, crate_)?;
-        self.generate_function_declarations(ast, crate_)?;
+        // Resolve every alias once so that errors in their definitions are reported against this file
+        for alias in &ast.type_aliases {
+            Type::new(self, &crate_.name, &alias.type_)?;
+        }
 
-        Ok(())
+        self.generate_struct_fields(ast, source, crate_)
+    }
+
+    /// Adds function declarations. This must be called after [`Self::generate_struct_bodies`]
+    pub fn add_functions(
+        &mut self,
+        ast: &ast::Module,
+        crate_: &Crate<'ctx>,
+    ) -> Result<(), Diagnostic> {
+        self.generate_function_declarations(ast, crate_)?;
+        self.resolve_drop_functions(ast, crate_)
     }
 
     #[allow(clippy::match_wildcard_for_single_variants)]
@@ -157,10 +221,12 @@ impl<'ctx> CodegenContext<'ctx> {
             generator.generate_function(function, &mut scope)?;
         }
 
-        if self
-            .main_crate
-            .as_deref()
-            .is_some_and(|n| n == &crate_.name)
+        // Libraries don't have an entrypoint even if they contain a `main` function
+        if params.crate_type == cmdline::CrateType::Bin
+            && self
+                .main_crate
+                .as_deref()
+                .is_some_and(|n| n == &crate_.name)
         {
             generator.generate_entrypoint()?;
         }
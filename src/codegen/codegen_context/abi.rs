@@ -0,0 +1,209 @@
+use wllvm::{attribute::AttrKind, type_::FnType, value::FnValue};
+
+use crate::codegen::{codegen_context::CodegenContext, namestore::FunctionSignature, types::Type};
+
+/// How a value is passed to or returned from a function that uses the C calling convention. This
+/// follows the x86-64 System V ABI. wlang has no floating point types, so every value that is
+/// passed in registers is passed in general-purpose registers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PassMode {
+    /// The value is passed as its LLVM type
+    Direct,
+    /// The value is zero-sized or uninstantiable and is not passed at all
+    Ignore,
+    /// The value is passed in registers as one integer of the given width for each eightbyte
+    Cast(Vec<u32>),
+    /// The value is passed in memory. Parameters are passed as a `byval` pointer to a copy of the
+    /// value, and return values are written to a `sret` pointer that the caller passes as the
+    /// first parameter
+    Indirect,
+}
+
+/// A function signature lowered to the C calling convention
+pub struct CSignature {
+    pub params: Vec<PassMode>,
+    pub return_: PassMode,
+}
+
+impl<'ctx> CodegenContext<'ctx> {
+    pub(in crate::codegen) fn pass_mode(&self, type_: &Type) -> PassMode {
+        let Some(llvm_type) = type_.llvm_type(self) else {
+            return PassMode::Ignore;
+        };
+
+        let size = llvm_type.size_bytes(&self.target_data);
+
+        if size == 0 {
+            return PassMode::Ignore;
+        }
+
+        match type_ {
+            Type::i(_) | Type::bool | Type::char => return PassMode::Direct,
+            Type::Slice(_) | Type::Struct { .. } => {}
+            // `str`s are rejected in C signatures
+            Type::str | Type::unit | Type::never => unreachable!(),
+        }
+
+        if size > 16 || !self.has_aligned_fields(type_) {
+            return PassMode::Indirect;
+        }
+
+        let widths = (0..size)
+            .step_by(8)
+            .map(|offset| ((size - offset).min(8) * 8) as u32)
+            .collect();
+
+        PassMode::Cast(widths)
+    }
+
+    /// Checks if every field of a type is at an offset that is a multiple of its alignment. This
+    /// can only be false for packed structs
+    fn has_aligned_fields(&self, type_: &Type) -> bool {
+        let Type::Struct { path } = type_ else {
+            return true;
+        };
+
+        let struct_info = self
+            .name_store
+            .get_item_from_string(path)
+            .unwrap()
+            .as_struct()
+            .unwrap();
+
+        let llvm_type = struct_info.llvm_type.unwrap();
+
+        struct_info.fields.iter().all(|field| {
+            let offset = llvm_type.offset_of(&self.target_data, field.llvm_idx);
+
+            offset.is_multiple_of(u64::from(field.ty.alignment(self)))
+                && self.has_aligned_fields(&field.ty)
+        })
+    }
+
+    pub(in crate::codegen) fn c_signature(&self, signature: &FunctionSignature) -> CSignature {
+        CSignature {
+            params: signature
+                .params
+                .iter()
+                .map(|param| self.pass_mode(param))
+                .collect(),
+            return_: self.pass_mode(&signature.return_type),
+        }
+    }
+
+    /// Gets the type that a value passed with [`PassMode::Cast`] is converted to. Values that
+    /// take up two eightbytes are converted to a struct of two integers
+    pub(in crate::codegen) fn cast_type(&self, widths: &[u32]) -> wllvm::Type<'ctx> {
+        let eightbytes: Vec<wllvm::Type> = widths
+            .iter()
+            .map(|&width| *self.context.int_type(width))
+            .collect();
+
+        if let [eightbyte] = *eightbytes {
+            eightbyte
+        } else {
+            *self.context.struct_type(&eightbytes, false)
+        }
+    }
+
+    /// Gets the LLVM type of a function with the C calling convention
+    pub(in crate::codegen) fn c_fn_type(&self, signature: &FunctionSignature) -> FnType<'ctx> {
+        let c_signature = self.c_signature(signature);
+        let ptr_type = *self.context.ptr_type();
+
+        let mut params = Vec::new();
+
+        let return_type = match &c_signature.return_ {
+            PassMode::Direct => signature.return_type.llvm_type(self).unwrap(),
+            PassMode::Ignore => *self.context.void_type(),
+            PassMode::Cast(widths) => self.cast_type(widths),
+            PassMode::Indirect => {
+                params.push(ptr_type);
+                *self.context.void_type()
+            }
+        };
+
+        for (param, pass_mode) in signature.params.iter().zip(&c_signature.params) {
+            match pass_mode {
+                PassMode::Direct => params.push(param.llvm_type(self).unwrap()),
+                PassMode::Ignore => {}
+                PassMode::Cast(widths) => {
+                    params.extend(widths.iter().map(|&width| *self.context.int_type(width)));
+                }
+                PassMode::Indirect => params.push(ptr_type),
+            }
+        }
+
+        self.context.fn_type(return_type, &params, false)
+    }
+
+    /// Adds the parameter and return value attributes that the C calling convention requires to
+    /// a function of type [`Self::c_fn_type`]
+    pub(in crate::codegen) fn add_c_attributes(
+        &self,
+        function: FnValue<'ctx>,
+        signature: &FunctionSignature,
+    ) {
+        let c_signature = self.c_signature(signature);
+        let mut idx = 0;
+
+        match c_signature.return_ {
+            PassMode::Direct => {
+                if let Some(extension) = self.extension_attribute(&signature.return_type) {
+                    function.add_ret_attribute(self.context.attribute(extension));
+                }
+            }
+            PassMode::Indirect => {
+                let return_type = signature.return_type.llvm_type(self).unwrap();
+
+                function.add_param_attribute(
+                    0,
+                    self.context.attribute(AttrKind::StructRet(return_type)),
+                );
+                function.add_param_attribute(0, self.context.attribute(AttrKind::NoAlias()));
+
+                idx += 1;
+            }
+            PassMode::Ignore | PassMode::Cast(_) => {}
+        }
+
+        for (param, pass_mode) in signature.params.iter().zip(c_signature.params) {
+            match pass_mode {
+                PassMode::Direct => {
+                    if let Some(extension) = self.extension_attribute(param) {
+                        function.add_param_attribute(idx, self.context.attribute(extension));
+                    }
+
+                    idx += 1;
+                }
+                PassMode::Ignore => {}
+                PassMode::Cast(widths) => idx += widths.len() as u32,
+                PassMode::Indirect => {
+                    let llvm_type = param.llvm_type(self).unwrap();
+                    let alignment = param.alignment(self).max(8);
+
+                    function.add_param_attribute(
+                        idx,
+                        self.context.attribute(AttrKind::ByVal(llvm_type)),
+                    );
+                    function.add_param_attribute(
+                        idx,
+                        self.context
+                            .attribute(AttrKind::Alignment(u64::from(alignment))),
+                    );
+
+                    idx += 1;
+                }
+            }
+        }
+    }
+
+    /// Integers that are smaller than 32 bits are extended to 32 bits by the caller
+    fn extension_attribute(&self, type_: &Type) -> Option<AttrKind<'ctx>> {
+        match *type_ {
+            Type::bool => Some(AttrKind::ZExt()),
+            Type::i(n) if n < 32 => Some(AttrKind::SExt()),
+            _ => None,
+        }
+    }
+}
@@ -23,6 +23,9 @@ impl<'ctx> CodegenContext<'ctx> {
         let crate_name = &*crate_.name;
         let module = &crate_.llvm_module;
 
+        // The global allocator functions that this module replaces
+        let mut replaced_allocators = Vec::new();
+
         for function in &ast.functions {
             let params: Result<Vec<(S<&str>, Type)>, _> = function
                 .params
@@ -41,34 +44,132 @@ impl<'ctx> CodegenContext<'ctx> {
                 .as_ref()
                 .map_or(Ok(Type::unit), |t| Type::new(self, &crate_.name, t))?;
 
-            let mut no_mangle = false;
+            let is_extern = function.body.is_none();
 
             for attr in &function.attributes {
                 match **attr {
-                    ast::Attribute::NoMangle => no_mangle = true,
+                    // `extern` functions are only declarations
+                    _ if is_extern => return Err(codegen::error::extern_function_attribute(attr)),
+                    // the C wrapper is generated by CodegenUnit::generate_c_export
+                    ast::Attribute::NoMangle => {}
                     ast::Attribute::Intrinsic(_) => {}
+                    // the allocator symbol is generated by CodegenUnit::generate_function
+                    ast::Attribute::GlobalAllocator(kind, default) => {
+                        // Both functions only take `i64`s
+                        let (num_params, expected_return, signature) = match kind {
+                            "alloc" => (2, Type::i(64), "fn(i64, i64) -> i64"),
+                            "dealloc" => (3, Type::unit, "fn(i64, i64, i64)"),
+                            _ => return Err(error::invalid_global_allocator(S(kind, attr.1))),
+                        };
+
+                        let valid_params = params.len() == num_params
+                            && params.iter().all(|(_, type_)| *type_ == Type::i(64));
+
+                        if !valid_params || return_type != expected_return {
+                            return Err(error::global_allocator_signature(
+                                function.params.1,
+                                signature,
+                            ));
+                        }
+
+                        // Only default allocators may be defined more than once
+                        if !default {
+                            if let Some(other_crate) = self.global_allocators.get(kind) {
+                                return Err(error::duplicate_global_allocator(
+                                    S(kind, attr.1),
+                                    other_crate,
+                                    crate_name,
+                                ));
+                            }
+
+                            self.global_allocators
+                                .insert(kind.to_owned(), crate_name.to_owned());
+                            replaced_allocators.push(S(kind, attr.1));
+                        }
+                    }
+                    ast::Attribute::Align(_) => return Err(error::align_outside_struct(attr.1)),
                     _ => return Err(codegen::error::non_function_attribute(attr)),
                 }
             }
 
-            let private = function.visibility == Visibility::Private && !no_mangle;
+            let uses_c_abi = is_extern
+                || function
+                    .attributes
+                    .iter()
+                    .any(|attr| **attr == ast::Attribute::NoMangle);
+
+            // Without pointer types, a `str` can't be converted to the `char *` that C expects
+            if uses_c_abi {
+                let param_types = params
+                    .iter()
+                    .zip(function.params.iter())
+                    .map(|((_, type_), (_, path))| (type_, path.1));
+                let return_types = function
+                    .return_type
+                    .as_ref()
+                    .map(|path| (&return_type, path.1));
+
+                if let Some((_, span)) = param_types
+                    .chain(return_types)
+                    .find(|(type_, _)| **type_ == Type::str)
+                {
+                    return Err(error::c_abi_str(span));
+                }
+            }
+
+            // `main` can optionally take the command-line arguments
+            let valid_main_params = match params.as_slice() {
+                [] => true,
+                [(_, Type::Slice(element))] => **element == Type::str,
+                _ => false,
+            };
+
+            // The value returned from `main` is used as the exit code of the program
+            let valid_main_return_type =
+                matches!(return_type, Type::unit | Type::never | Type::i(32));
 
-            let fn_name = if no_mangle {
+            let private = function.visibility == Visibility::Private && !is_extern;
+
+            let fn_name = if is_extern {
                 Cow::from(function.name)
             } else {
                 Cow::from(format!("_WL@{crate_name}::{}", function.name))
             };
 
-            let llvm_return_type = return_type.llvm_type(self);
+            let param_names = params
+                .iter()
+                .zip(function.params.iter())
+                .map(|((_, type_), (_, path))| type_.written_name(self, crate_name, path))
+                .collect();
+            let return_type_name = function.return_type.as_ref().map_or_else(
+                || return_type.to_string(),
+                |path| return_type.written_name(self, crate_name, path),
+            );
+
+            let signature = FunctionSignature {
+                params: params.into_iter().map(|(_, t)| t).collect(),
+                return_type,
+                param_names,
+                return_type_name,
+            };
 
-            let ll_function = module.add_function(
-                c"",
+            let llvm_return_type = signature.return_type.llvm_type(self);
+
+            let fn_type = if is_extern {
+                self.c_fn_type(&signature)
+            } else {
                 self.context.fn_type(
                     llvm_return_type.unwrap_or(*self.core_types.unit),
                     &llvm_param_types,
                     false,
-                ),
-            );
+                )
+            };
+
+            let ll_function = module.add_function(c"", fn_type);
+
+            if is_extern {
+                self.add_c_attributes(ll_function, &signature);
+            }
 
             if llvm_return_type.is_none() {
                 ll_function.add_attribute(self.context.attribute(AttrKind::NoReturn()));
@@ -84,12 +185,10 @@ impl<'ctx> CodegenContext<'ctx> {
             if !self.name_store.add_function(
                 &[crate_name, function.name],
                 FunctionInfo {
-                    signature: FunctionSignature {
-                        params: params.into_iter().map(|(_, t)| t).collect(),
-                        return_type,
-                    },
+                    signature,
                     function: ll_function,
                     visibility: function.visibility,
+                    c_abi: is_extern,
                 },
             ) {
                 return Err(codegen::error::item_already_defined(S(
@@ -98,25 +197,34 @@ impl<'ctx> CodegenContext<'ctx> {
                 )));
             }
 
-            if function.name == "main" {
+            if function.name == "main" && !is_extern {
                 if let Some(other_crate) = &self.main_crate {
                     return Err(error::duplicate_main(&other_crate, crate_name, function.1));
                 }
 
                 self.main_crate = Some(crate_name.to_owned());
 
-                if !function.params.is_empty() {
+                if !valid_main_params {
                     return Err(error::main_arguments(function.params.1));
                 }
 
-                if let Some(return_type) = &function.return_type {
-                    if return_type.get(0).is_some_and(|t| **t != "()") {
-                        return Err(error::main_return_type(return_type.1));
-                    }
+                if let (false, Some(return_type)) = (valid_main_return_type, &function.return_type)
+                {
+                    return Err(error::main_return_type(return_type.1));
                 }
             }
         }
 
+        // Memory has to be freed by the allocator that allocated it
+        if let [replaced] = *replaced_allocators {
+            let missing = if *replaced == "alloc" {
+                "dealloc"
+            } else {
+                "alloc"
+            };
+            return Err(error::partial_global_allocator(replaced, missing));
+        }
+
         Ok(())
     }
 }
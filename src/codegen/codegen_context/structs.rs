@@ -1,7 +1,13 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use wllvm::value::Linkage;
+use wutil::Span;
+
 use crate::{
     codegen::{
         self,
         codegen_context::{CodegenContext, Crate},
+        const_value::ConstExpr,
         namestore::{FieldInfo, NameStoreEntry},
         types::Type,
     },
@@ -10,29 +16,58 @@ use crate::{
     util,
 };
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum VisitState {
+    InProgress,
+    Done,
+}
+
 impl<'ctx> CodegenContext<'ctx> {
-    pub(super) fn generate_struct_bodies(
+    /// Resolves the fields of every struct in a crate. The LLVM struct bodies are set later by
+    /// [`Self::generate_struct_bodies`] once the fields of every crate are known.
+    pub(super) fn generate_struct_fields(
         &mut self,
         ast: &ast::Module,
         source: &str,
         crate_: &Crate<'ctx>,
     ) -> Result<(), Diagnostic> {
         for struct_ in &ast.structs {
-            let mut packed = false;
+            let mut packed = None;
+            let mut repr_c = false;
+            let mut copy = false;
+            let mut explicit_align = None;
 
             for attr in &struct_.attributes {
                 match &**attr {
-                    ast::Attribute::Packed => packed = true,
+                    ast::Attribute::Packed => packed = Some(attr.1),
+                    ast::Attribute::ReprC => repr_c = true,
+                    ast::Attribute::Copy => copy = true,
+                    &ast::Attribute::Align(align) => {
+                        if !align.is_power_of_two() {
+                            return Err(codegen::error::invalid_alignment(S(align, attr.1)));
+                        }
+
+                        explicit_align = Some(S(align, attr.1));
+                    }
+                    // drop functions are resolved by Self::resolve_drop_functions
+                    ast::Attribute::Drop(_) => {}
                     _ => return Err(codegen::error::non_struct_attribute(attr)),
                 }
             }
 
+            // A packed struct has an alignment of one, so a larger alignment contradicts it
+            if let Some((packed, align)) = packed.zip(explicit_align) {
+                if *align > 1 {
+                    return Err(codegen::error::packed_and_aligned(packed, align));
+                }
+            }
+
             let line_no = util::line_and_col(source, struct_.1.start).0 as u32;
 
             let mut fields = Vec::new();
             let mut field_names: Vec<S<&str>> = Vec::new();
 
-            for field in &struct_.fields {
+            for (field_idx, field) in struct_.fields.iter().enumerate() {
                 match field_names.binary_search_by(|f| f.cmp(field.name)) {
                     Ok(idx) => {
                         let field1 = field_names[idx];
@@ -43,18 +78,98 @@ impl<'ctx> CodegenContext<'ctx> {
 
                 let line_no = util::line_and_col(source, field.1.start).0 as u32;
                 let ty = Type::new(self, &crate_.name, &field.type_)?;
+                let type_name = ty.written_name(self, &crate_.name, &field.type_);
+                let default_expr = field.default.as_ref().map(ConstExpr::new).transpose()?;
 
                 fields.push(FieldInfo {
                     name: field.name.to_owned(),
                     ty,
+                    type_name,
                     line_no,
+                    span: field.1,
+                    visibility: field.visibility,
+                    // placeholder value; this will be replaced by Self::generate_struct_bodies
+                    llvm_idx: field_idx as u32,
+                    default_expr,
+                    default: None,
                 });
             }
 
-            let llvm_fields: Option<Vec<wllvm::Type>> = fields
+            let NameStoreEntry::Struct(struct_info) = self
+                .name_store
+                .get_item_in_crate_mut(&crate_.name, struct_.name)
+            else {
+                unreachable!()
+            };
+
+            struct_info.fields = fields;
+            struct_info.packed = packed.is_some();
+            struct_info.repr_c = repr_c;
+            struct_info.copy = copy;
+            struct_info.explicit_align = explicit_align.map(|a| *a);
+            struct_info.line_no = line_no;
+            struct_info.file_no = crate_.file_no;
+        }
+
+        Ok(())
+    }
+
+    /// Resolves the functions set with `#[drop(function)]` on the structs of a crate. A drop
+    /// function must be defined in the same crate as its struct and take the struct by value.
+    pub(super) fn resolve_drop_functions(
+        &mut self,
+        ast: &ast::Module,
+        crate_: &Crate<'ctx>,
+    ) -> Result<(), Diagnostic> {
+        for struct_ in &ast.structs {
+            let Some(attr) = struct_
+                .attributes
                 .iter()
-                .map(|field| field.ty.llvm_type(&self))
-                .collect();
+                .rev()
+                .find(|attr| matches!(***attr, ast::Attribute::Drop(_)))
+            else {
+                continue;
+            };
+
+            let ast::Attribute::Drop(fn_name) = **attr else {
+                unreachable!()
+            };
+
+            let struct_path = format!("{}::{}", crate_.name, struct_.name);
+
+            let struct_info = self
+                .name_store
+                .get_item_from_string(&struct_path)
+                .unwrap()
+                .as_struct()
+                .unwrap();
+
+            if struct_info.copy {
+                return Err(codegen::error::copy_and_drop(&struct_path, attr.1));
+            }
+
+            let function = self
+                .name_store
+                .get_item_in_crate(&crate_.name, S(fn_name, attr.1))?
+                .as_function()
+                .ok_or_else(|| codegen::error::not_function(S(fn_name, attr.1)))?;
+
+            let struct_type = Type::Struct {
+                path: struct_path.clone(),
+            };
+
+            if function.signature.params != [struct_type]
+                || function.signature.return_type != Type::unit
+            {
+                return Err(codegen::error::invalid_drop_function(
+                    S(fn_name, attr.1),
+                    &struct_path,
+                ));
+            }
+
+            // The struct may be dropped in other crates, so the function must always be visible
+            // to the linker
+            function.function.set_linkage(Linkage::External);
 
             let NameStoreEntry::Struct(struct_info) = self
                 .name_store
@@ -63,22 +178,276 @@ impl<'ctx> CodegenContext<'ctx> {
                 unreachable!()
             };
 
-            if let Some(llvm_fields) = llvm_fields {
-                struct_info
-                    .llvm_type
-                    .as_ref()
-                    .unwrap()
-                    .set_body(&llvm_fields, packed);
-            } else {
+            struct_info.drop_fn = Some(format!("{}::{fn_name}", crate_.name));
+        }
+
+        Ok(())
+    }
+
+    /// Sets the LLVM bodies of every struct in every crate.
+    ///
+    /// Structs are visited depth-first so that the body of a struct is always set after the bodies
+    /// of the structs that it contains. If a struct (indirectly) contains itself, an error is
+    /// returned along with the number of the file that it should be rendered with.
+    pub fn generate_struct_bodies(&mut self) -> Result<(), (usize, Diagnostic)> {
+        let mut paths = self.name_store.struct_paths();
+        paths.sort_unstable();
+
+        let mut states = HashMap::new();
+        let mut order = Vec::new();
+
+        for path in &paths {
+            self.visit_struct(path, &mut Vec::new(), &mut states, &mut order)?;
+        }
+
+        for path in order {
+            self.evaluate_field_defaults(&path)?;
+            self.check_copy_fields(&path)?;
+
+            let struct_info = self
+                .name_store
+                .get_item_from_string(&path)
+                .unwrap()
+                .as_struct()
+                .unwrap();
+
+            let llvm_fields: Option<Vec<wllvm::Type>> = struct_info
+                .fields
+                .iter()
+                .map(|field| field.ty.llvm_type(self))
+                .collect();
+
+            let field_aligns: Vec<u32> = struct_info
+                .fields
+                .iter()
+                .map(|field| field.ty.alignment(self))
+                .collect();
+
+            let packed = struct_info.packed;
+            let repr_c = struct_info.repr_c;
+            let explicit_align = struct_info.explicit_align.unwrap_or(1);
+
+            let Some(llvm_fields) = llvm_fields else {
+                let Some(NameStoreEntry::Struct(struct_info)) =
+                    self.name_store.get_item_from_string_mut(&path)
+                else {
+                    unreachable!()
+                };
+
                 struct_info.llvm_type = None;
+                continue;
+            };
+
+            let alignment = if packed {
+                explicit_align
+            } else {
+                field_aligns.iter().copied().fold(explicit_align, u32::max)
+            };
+
+            // Unless the layout is fixed, fields are sorted by descending alignment (keeping
+            // declaration order for equal alignments) which minimizes the amount of padding
+            let mut layout: Vec<usize> = (0..llvm_fields.len()).collect();
+            if !packed && !repr_c {
+                layout.sort_by_key(|&i| Reverse(field_aligns[i]));
             }
 
-            struct_info.fields = fields;
-            struct_info.packed = packed;
-            struct_info.line_no = line_no;
-            struct_info.file_no = crate_.file_no;
+            // LLVM only knows the natural alignment of each type, so if any alignment was
+            // raised by `#[align(N)]`, the struct is laid out manually as a packed struct with
+            // explicit padding fields
+            let natural_align = |i: usize| llvm_fields[i].alignment(&self.target_data);
+            let manual_layout = !packed
+                && (alignment > (0..llvm_fields.len()).map(natural_align).fold(1, u32::max)
+                    || (0..llvm_fields.len()).any(|i| field_aligns[i] > natural_align(i)));
+
+            let i8 = self.context.int_type(8);
+            let mut llvm_indices = vec![0; llvm_fields.len()];
+            let mut body = Vec::with_capacity(layout.len());
+            let mut offset = 0;
+
+            let pad_to = |body: &mut Vec<wllvm::Type<'ctx>>, align: u32, offset: &mut u64| {
+                let padding_bytes = offset.next_multiple_of(u64::from(align)) - *offset;
+
+                if padding_bytes != 0 {
+                    body.push(*i8.array_type(padding_bytes));
+                    *offset += padding_bytes;
+                }
+            };
+
+            for field_idx in layout {
+                if manual_layout {
+                    pad_to(&mut body, field_aligns[field_idx], &mut offset);
+                    offset += llvm_fields[field_idx].size_bytes(&self.target_data);
+                }
+
+                llvm_indices[field_idx] = body.len() as u32;
+                body.push(llvm_fields[field_idx]);
+            }
+
+            if manual_layout {
+                pad_to(&mut body, alignment, &mut offset);
+            }
+
+            let Some(NameStoreEntry::Struct(struct_info)) =
+                self.name_store.get_item_from_string_mut(&path)
+            else {
+                unreachable!()
+            };
+
+            for (field, llvm_idx) in struct_info.fields.iter_mut().zip(llvm_indices) {
+                field.llvm_idx = llvm_idx;
+            }
+
+            struct_info.alignment = alignment;
+            struct_info
+                .llvm_type
+                .as_ref()
+                .unwrap()
+                .set_body(&body, packed || manual_layout);
+        }
+
+        Ok(())
+    }
+
+    /// Checks that every field of a `#[copy]` struct can be copied
+    fn check_copy_fields(&self, path: &str) -> Result<(), (usize, Diagnostic)> {
+        let struct_info = self
+            .name_store
+            .get_item_from_string(path)
+            .unwrap()
+            .as_struct()
+            .unwrap();
+
+        if !struct_info.copy {
+            return Ok(());
+        }
+
+        for field in &struct_info.fields {
+            if !field.ty.is_copy(self) {
+                return Err((
+                    struct_info.file_no,
+                    codegen::error::non_copy_field(path, S(&field.ty, field.span)),
+                ));
+            }
         }
 
         Ok(())
     }
+
+    /// Evaluates the default values of the fields of `path`. The defaults of every struct that it
+    /// contains must already be evaluated.
+    fn evaluate_field_defaults(&mut self, path: &str) -> Result<(), (usize, Diagnostic)> {
+        let struct_info = self
+            .name_store
+            .get_item_from_string(path)
+            .unwrap()
+            .as_struct()
+            .unwrap();
+
+        let crate_name = path.split("::").next().unwrap();
+
+        let defaults = struct_info
+            .fields
+            .iter()
+            .map(|field| {
+                field
+                    .default_expr
+                    .as_ref()
+                    .map(|expr| self.evaluate_const_expr(crate_name, expr, &field.ty))
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| (struct_info.file_no, err))?;
+
+        let Some(NameStoreEntry::Struct(struct_info)) =
+            self.name_store.get_item_from_string_mut(path)
+        else {
+            unreachable!()
+        };
+
+        for (field, default) in struct_info.fields.iter_mut().zip(defaults) {
+            field.default = default;
+        }
+
+        Ok(())
+    }
+
+    /// Adds `path` and every struct that it contains to `order` (dependencies first).
+    ///
+    /// `stack` contains the structs currently being visited along with the index of the field
+    /// that is being followed.
+    fn visit_struct(
+        &self,
+        path: &str,
+        stack: &mut Vec<(String, usize)>,
+        states: &mut HashMap<String, VisitState>,
+        order: &mut Vec<String>,
+    ) -> Result<(), (usize, Diagnostic)> {
+        match states.get(path) {
+            Some(VisitState::Done) => return Ok(()),
+            Some(VisitState::InProgress) => {
+                let start = stack.iter().position(|(p, _)| p == path).unwrap();
+                return Err(self.recursive_struct_error(&stack[start..]));
+            }
+            None => {}
+        }
+
+        states.insert(path.to_owned(), VisitState::InProgress);
+
+        let struct_info = self
+            .name_store
+            .get_item_from_string(path)
+            .unwrap()
+            .as_struct()
+            .unwrap();
+
+        for (idx, field) in struct_info.fields.iter().enumerate() {
+            let Type::Struct { path: field_path } = &field.ty else {
+                continue;
+            };
+
+            stack.push((path.to_owned(), idx));
+            self.visit_struct(field_path, stack, states, order)?;
+            stack.pop();
+        }
+
+        states.insert(path.to_owned(), VisitState::Done);
+        order.push(path.to_owned());
+
+        Ok(())
+    }
+
+    fn recursive_struct_error(&self, cycle: &[(String, usize)]) -> (usize, Diagnostic) {
+        let get_struct = |path: &str| {
+            self.name_store
+                .get_item_from_string(path)
+                .unwrap()
+                .as_struct()
+                .unwrap()
+        };
+
+        let file_no = get_struct(&cycle[0].0).file_no;
+
+        let paths: Vec<&str> = cycle.iter().map(|(path, _)| &**path).collect();
+
+        // Hints can only be rendered for fields in the same file as the error, so the fields in
+        // other files are named in the message instead
+        let mut fields: Vec<(Span, &Type)> = Vec::new();
+        let mut other_fields: Vec<(&str, &str)> = Vec::new();
+
+        for (path, idx) in cycle {
+            let struct_info = get_struct(path);
+            let field = &struct_info.fields[*idx];
+
+            if struct_info.file_no == file_no {
+                fields.push((field.span, &field.ty));
+            } else {
+                other_fields.push((path, &field.name));
+            }
+        }
+
+        (
+            file_no,
+            codegen::error::recursive_struct(&paths, &fields, &other_fields),
+        )
+    }
 }
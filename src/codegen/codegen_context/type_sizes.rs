@@ -0,0 +1,194 @@
+use std::fmt::Write as _;
+
+use crate::{cmdline::TypeSizesFormat, codegen::codegen_context::CodegenContext};
+
+struct FieldLayout<'a> {
+    name: &'a str,
+    offset: u64,
+    size: u64,
+    alignment: u32,
+    /// The number of padding bytes between the previous field (or the start of the struct) and this
+    /// field
+    padding: u64,
+}
+
+struct StructLayout<'a> {
+    path: String,
+    size: u64,
+    alignment: u32,
+    /// The fields of the struct ordered by offset
+    fields: Vec<FieldLayout<'a>>,
+    /// The number of padding bytes after the last field
+    end_padding: u64,
+}
+
+impl<'ctx> CodegenContext<'ctx> {
+    /// Generates a report of the layout of every instantiable struct. This must be called after
+    /// [`Self::generate_struct_bodies`]
+    pub fn type_sizes(&self, format: TypeSizesFormat) -> String {
+        let layouts = self.struct_layouts();
+
+        match format {
+            TypeSizesFormat::Human => format_human(&layouts),
+            TypeSizesFormat::Json => format_json(&layouts),
+        }
+    }
+
+    fn struct_layouts(&self) -> Vec<StructLayout<'_>> {
+        let mut paths = self.name_store.struct_paths();
+        paths.sort_unstable();
+
+        let mut layouts = Vec::new();
+
+        for path in paths {
+            let struct_info = self
+                .name_store
+                .get_item_from_string(&path)
+                .unwrap()
+                .as_struct()
+                .unwrap();
+
+            let Some(llvm_type) = struct_info.llvm_type else {
+                continue;
+            };
+
+            let mut fields: Vec<FieldLayout> = struct_info
+                .fields
+                .iter()
+                .map(|field| FieldLayout {
+                    name: &field.name,
+                    offset: llvm_type.offset_of(&self.target_data, field.llvm_idx),
+                    size: field
+                        .ty
+                        .llvm_type(self)
+                        .unwrap()
+                        .size_bytes(&self.target_data),
+                    alignment: field.ty.alignment(self),
+                    padding: 0,
+                })
+                .collect();
+
+            fields.sort_by_key(|field| field.offset);
+
+            let mut end = 0;
+            for field in &mut fields {
+                field.padding = field.offset - end;
+                end = field.offset + field.size;
+            }
+
+            let size = llvm_type.size_bytes(&self.target_data);
+
+            layouts.push(StructLayout {
+                path,
+                size,
+                alignment: struct_info.alignment,
+                fields,
+                end_padding: size - end,
+            });
+        }
+
+        layouts
+    }
+}
+
+fn format_human(layouts: &[StructLayout]) -> String {
+    let mut out = String::new();
+
+    for layout in layouts {
+        writeln!(
+            out,
+            "type `{}`: {} bytes, alignment: {} bytes",
+            layout.path, layout.size, layout.alignment
+        )
+        .unwrap();
+
+        for field in &layout.fields {
+            if field.padding != 0 {
+                writeln!(out, "    padding: {} bytes", field.padding).unwrap();
+            }
+
+            writeln!(
+                out,
+                "    field `.{}`: {} bytes, offset: {} bytes, alignment: {} bytes",
+                field.name, field.size, field.offset, field.alignment
+            )
+            .unwrap();
+        }
+
+        if layout.end_padding != 0 {
+            writeln!(out, "    end padding: {} bytes", layout.end_padding).unwrap();
+        }
+    }
+
+    out
+}
+
+fn format_json(layouts: &[StructLayout]) -> String {
+    let mut out = String::from("[");
+
+    for (i, layout) in layouts.iter().enumerate() {
+        if i != 0 {
+            out.push(',');
+        }
+
+        write!(
+            out,
+            "{{\"type\":{},\"size\":{},\"alignment\":{},\"fields\":[",
+            json_string(&layout.path),
+            layout.size,
+            layout.alignment
+        )
+        .unwrap();
+
+        for (j, field) in layout.fields.iter().enumerate() {
+            if j != 0 {
+                out.push(',');
+            }
+
+            write!(
+                out,
+                "{{\"name\":{},\"offset\":{},\"size\":{},\"alignment\":{},\"padding\":{}}}",
+                json_string(field.name),
+                field.offset,
+                field.size,
+                field.alignment,
+                field.padding
+            )
+            .unwrap();
+        }
+
+        write!(out, "],\"end_padding\":{}}}", layout.end_padding).unwrap();
+    }
+
+    out.push_str("]\n");
+    out
+}
+
+/// Quotes and escapes a string for use in JSON
+fn json_string(str: &str) -> String {
+    let mut out = String::from("\"");
+
+    for c in str.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::json_string;
+
+    #[test]
+    fn json_string_test() {
+        assert_eq!(json_string("test::Point"), r#""test::Point""#);
+        assert_eq!(json_string(r#"a"b\c"#), r#""a\"b\\c""#);
+        assert_eq!(json_string("tab\tnull\0"), r#""tab\u0009null\u0000""#);
+    }
+}
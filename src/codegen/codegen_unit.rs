@@ -1,23 +1,29 @@
-use wllvm::{Builder, Module as LlvmModule};
+use std::cell::{Cell, RefCell};
+
+use wllvm::{value::PtrValue, BasicBlock, Builder, Module as LlvmModule};
 
 use crate::{
     codegen::{
         codegen_context::{CodegenContext, Crate},
-        codegen_unit::debug::DebugContext,
+        codegen_unit::{debug::DebugContext, moves::MoveEvent},
         error,
         scope::Scope,
         values::{GenericValue, MutValue, RValue},
     },
     error_handling::{Diagnostic, Spanned as S},
-    parser::ast::{self, Statement},
+    parser::ast::{self, Expression, Statement},
 };
 
 use super::types::Type;
 
+mod comparison;
 pub(super) mod debug;
+mod drop;
 mod expression;
+mod ffi;
 mod function;
-mod main;
+pub(super) mod main;
+mod moves;
 
 pub struct CodegenUnit<'m, 'ctx> {
     pub(super) c: &'m CodegenContext<'ctx>,
@@ -27,6 +33,10 @@ pub struct CodegenUnit<'m, 'ctx> {
     pub(super) crate_name: &'m str,
     pub(super) source: &'m str,
     pub(super) file_no: usize,
+    /// The number of variables that have been declared so far. This is used to create variable ids
+    variable_count: Cell<usize>,
+    /// The move events of the function that is being generated
+    move_events: RefCell<Vec<(BasicBlock<'ctx>, MoveEvent)>>,
 }
 
 impl<'m, 'ctx> CodegenUnit<'m, 'ctx> {
@@ -49,6 +59,8 @@ impl<'m, 'ctx> CodegenUnit<'m, 'ctx> {
             crate_name,
             source,
             file_no,
+            variable_count: Cell::new(0),
+            move_events: RefCell::new(Vec::new()),
         }
     }
 
@@ -58,15 +70,18 @@ impl<'m, 'ctx> CodegenUnit<'m, 'ctx> {
         statement: S<&ast::Statement>,
     ) -> Result<Option<RValue>, Diagnostic> {
         match *statement {
-            Statement::Expression(expr) => self
-                .generate_rvalue(S(expr, statement.1), scope)
-                .map(|v| Some(v)),
+            Statement::Expression(expr) => {
+                let value = self.generate_rvalue(S(expr, statement.1), scope)?;
+                self.drop_temporary(expr, &value);
+
+                Ok(Some(value))
+            }
             Statement::Let {
                 name,
                 value,
                 mutable,
             } => {
-                let orig_val = self.generate_rvalue(value.as_sref(), scope)?;
+                let orig_val = self.generate_owned_rvalue(value.as_sref(), scope)?;
 
                 let unreachable = orig_val.val.is_none();
 
@@ -84,9 +99,10 @@ impl<'m, 'ctx> CodegenUnit<'m, 'ctx> {
                 }))
             }
             Statement::Struct(_) => todo!(),
+            Statement::TypeAlias(_) => Err(error::local_type_alias(statement.1)),
             Statement::Assign { lhs, rhs } => {
                 let lhs_val = self.generate_mutvalue(lhs.as_sref(), scope)?;
-                let rhs_val = self.generate_rvalue(rhs.as_sref(), scope)?;
+                let rhs_val = self.generate_owned_rvalue(rhs.as_sref(), scope)?;
 
                 let unreachable = lhs_val.ptr.is_none() || rhs_val.val.is_none();
 
@@ -102,7 +118,21 @@ impl<'m, 'ctx> CodegenUnit<'m, 'ctx> {
                     return Ok(None);
                 };
 
+                // The old value is dropped before it is overwritten
+                if let Expression::Identifier(name) = **lhs {
+                    self.drop_overwritten_variable(scope, name);
+                } else if let Some(llvm_type) = lhs_val.type_.llvm_type(self.c) {
+                    if lhs_val.type_.needs_drop(self.c) {
+                        let old_val = self.builder.build_load(llvm_type, lhs_ptr, c"");
+                        self.generate_drop(old_val, &lhs_val.type_);
+                    }
+                }
+
                 self.builder.build_store(rhs_val, lhs_ptr);
+
+                if let Expression::Identifier(name) = **lhs {
+                    self.reinitialize_variable(scope, name);
+                }
                 Ok(unreachable.then_some(RValue {
                     val: None,
                     type_: Type::never,
@@ -111,4 +141,20 @@ impl<'m, 'ctx> CodegenUnit<'m, 'ctx> {
             Statement::Function(_) => todo!(),
         }
     }
+
+    /// Allocates stack space in the entry block of the function that `builder` is generating.
+    /// Allocas in other blocks run every time their block does, so they would grow the stack
+    /// inside loops.
+    pub(crate) fn build_entry_alloca(
+        &self,
+        builder: &Builder<'ctx>,
+        type_: wllvm::Type<'ctx>,
+    ) -> PtrValue<'ctx> {
+        let function = builder.current_block().unwrap().parent();
+
+        let entry_builder = self.c.context.create_builder();
+        entry_builder.position_at_start(function.entry_block());
+
+        entry_builder.build_alloca(type_, c"")
+    }
 }
@@ -0,0 +1,262 @@
+use std::ffi::CString;
+
+use wllvm::{
+    builder::IntPredicate,
+    value::{FnValue, IntValue, Linkage, StructValue},
+    Builder,
+};
+
+use crate::codegen::{codegen_unit::CodegenUnit, types::Type};
+
+impl<'ctx> CodegenUnit<'_, 'ctx> {
+    /// Generates `lhs == rhs` for two values of type `type_`. `type_` must satisfy
+    /// [`Type::has_equality`].
+    ///
+    /// Strings and structs are compared by helper functions that are generated once per module.
+    pub(crate) fn generate_equality(
+        &self,
+        builder: &Builder<'ctx>,
+        type_: &Type,
+        lhs: wllvm::Value<'ctx>,
+        rhs: wllvm::Value<'ctx>,
+    ) -> IntValue<'ctx> {
+        match type_ {
+            Type::i(_) | Type::bool | Type::char => {
+                let (Ok(lhs), Ok(rhs)) = (IntValue::try_from(lhs), IntValue::try_from(rhs)) else {
+                    unreachable!()
+                };
+
+                builder.build_icmp(IntPredicate::EQ, lhs, rhs, c"")
+            }
+            Type::unit => self.c.core_types.bool.const_(1, false),
+            Type::str => {
+                let val = builder.build_fn_call(self.str_eq_fn(), &[lhs, rhs], c"");
+                IntValue::try_from(val).unwrap()
+            }
+            Type::Struct { path } => {
+                let val = builder.build_fn_call(self.struct_eq_fn(path), &[lhs, rhs], c"");
+                IntValue::try_from(val).unwrap()
+            }
+            Type::Slice(_) | Type::never => unreachable!(),
+        }
+    }
+
+    /// Generates `lhs < rhs` for two `str`s. Strings are compared lexicographically by their
+    /// bytes.
+    pub(crate) fn generate_str_less_than(
+        &self,
+        lhs: wllvm::Value<'ctx>,
+        rhs: wllvm::Value<'ctx>,
+    ) -> IntValue<'ctx> {
+        let val = self
+            .builder
+            .build_fn_call(self.str_lt_fn(), &[lhs, rhs], c"");
+
+        IntValue::try_from(val).unwrap()
+    }
+
+    /// Gets the helper function called `name` with the signature `fn(type_, type_) -> bool`,
+    /// generating its body with `generate_body` if it does not exist in this module yet
+    fn comparison_fn(
+        &self,
+        name: &str,
+        type_: wllvm::Type<'ctx>,
+        generate_body: impl FnOnce(&Builder<'ctx>, FnValue<'ctx>),
+    ) -> FnValue<'ctx> {
+        if let Some(function) = self.module.get_function(name) {
+            return function;
+        }
+
+        let context = self.c.context;
+        let fn_type = context.fn_type(*self.c.core_types.bool, &[type_, type_], false);
+
+        let function = self
+            .module
+            .add_function(&CString::new(name).unwrap(), fn_type);
+        function.set_linkage(Linkage::Private);
+
+        // A separate builder is used so that the position and debug location of the main builder
+        // are left untouched
+        let builder = context.create_builder();
+        builder.position_at_end(function.add_basic_block(c""));
+
+        generate_body(&builder, function);
+
+        function
+    }
+
+    /// Gets the function that checks if two `str`s contain the same bytes
+    pub(crate) fn str_eq_fn(&self) -> FnValue<'ctx> {
+        self.comparison_fn(
+            "__wlab_str_eq",
+            *self.c.core_types.str,
+            |builder, function| {
+                let isize = self.c.core_types.isize;
+                let (lhs_len, lhs_ptr, rhs_len, rhs_ptr) = self.str_params(builder, function);
+
+                let loop_block = function.add_basic_block(c"");
+                let body_block = function.add_basic_block(c"");
+                let true_block = function.add_basic_block(c"");
+                let false_block = function.add_basic_block(c"");
+
+                let entry_block = builder.current_block().unwrap();
+                let same_len = builder.build_icmp(IntPredicate::EQ, lhs_len, rhs_len, c"");
+                builder.build_cond_br(same_len, loop_block, false_block);
+
+                builder.position_at_end(loop_block);
+                let idx = builder.build_phi(*isize, c"");
+                let idx_val = IntValue::try_from(*idx).unwrap();
+                let done = builder.build_icmp(IntPredicate::EQ, idx_val, lhs_len, c"");
+                builder.build_cond_br(done, true_block, body_block);
+
+                builder.position_at_end(body_block);
+                let lhs_byte = self.load_byte(builder, lhs_ptr, idx_val);
+                let rhs_byte = self.load_byte(builder, rhs_ptr, idx_val);
+                let next_idx = builder.build_add(idx_val, isize.const_(1, false), c"");
+                let same_byte = builder.build_icmp(IntPredicate::EQ, lhs_byte, rhs_byte, c"");
+                builder.build_cond_br(same_byte, loop_block, false_block);
+
+                idx.add_incoming(
+                    &[*isize.const_(0, false), *next_idx],
+                    &[entry_block, body_block],
+                );
+
+                builder.position_at_end(true_block);
+                builder.build_ret(*self.c.core_types.bool.const_(1, false));
+
+                builder.position_at_end(false_block);
+                builder.build_ret(*self.c.core_types.bool.const_(0, false));
+            },
+        )
+    }
+
+    /// Gets the function that checks if one `str` is lexicographically less than another
+    fn str_lt_fn(&self) -> FnValue<'ctx> {
+        self.comparison_fn(
+            "__wlab_str_lt",
+            *self.c.core_types.str,
+            |builder, function| {
+                let isize = self.c.core_types.isize;
+                let (lhs_len, lhs_ptr, rhs_len, rhs_ptr) = self.str_params(builder, function);
+
+                let loop_block = function.add_basic_block(c"");
+                let check_lhs_block = function.add_basic_block(c"");
+                let body_block = function.add_basic_block(c"");
+                let differ_block = function.add_basic_block(c"");
+                let true_block = function.add_basic_block(c"");
+                let false_block = function.add_basic_block(c"");
+
+                let entry_block = builder.current_block().unwrap();
+                builder.build_br(loop_block);
+
+                // If `rhs` ends first (or both strings are equal), `lhs` is not less than `rhs`.
+                // Otherwise, if `lhs` ends first, it is a prefix of `rhs`.
+                builder.position_at_end(loop_block);
+                let idx = builder.build_phi(*isize, c"");
+                let idx_val = IntValue::try_from(*idx).unwrap();
+                let rhs_end = builder.build_icmp(IntPredicate::EQ, idx_val, rhs_len, c"");
+                builder.build_cond_br(rhs_end, false_block, check_lhs_block);
+
+                builder.position_at_end(check_lhs_block);
+                let lhs_end = builder.build_icmp(IntPredicate::EQ, idx_val, lhs_len, c"");
+                builder.build_cond_br(lhs_end, true_block, body_block);
+
+                builder.position_at_end(body_block);
+                let lhs_byte = self.load_byte(builder, lhs_ptr, idx_val);
+                let rhs_byte = self.load_byte(builder, rhs_ptr, idx_val);
+                let next_idx = builder.build_add(idx_val, isize.const_(1, false), c"");
+                let same_byte = builder.build_icmp(IntPredicate::EQ, lhs_byte, rhs_byte, c"");
+                builder.build_cond_br(same_byte, loop_block, differ_block);
+
+                idx.add_incoming(
+                    &[*isize.const_(0, false), *next_idx],
+                    &[entry_block, body_block],
+                );
+
+                builder.position_at_end(differ_block);
+                let less = builder.build_icmp(IntPredicate::ULT, lhs_byte, rhs_byte, c"");
+                builder.build_ret(*less);
+
+                builder.position_at_end(true_block);
+                builder.build_ret(*self.c.core_types.bool.const_(1, false));
+
+                builder.position_at_end(false_block);
+                builder.build_ret(*self.c.core_types.bool.const_(0, false));
+            },
+        )
+    }
+
+    /// Gets the function that compares two values of the struct `path` field-by-field
+    fn struct_eq_fn(&self, path: &str) -> FnValue<'ctx> {
+        let struct_info = self
+            .c
+            .name_store
+            .get_item_from_string(path)
+            .unwrap()
+            .as_struct()
+            .unwrap();
+
+        let llvm_type = struct_info.llvm_type.unwrap();
+
+        self.comparison_fn(
+            &format!("__wlab_eq.{path}"),
+            *llvm_type,
+            |builder, function| {
+                let lhs = StructValue::try_from(function.param(0).unwrap()).unwrap();
+                let rhs = StructValue::try_from(function.param(1).unwrap()).unwrap();
+
+                let mut result = self.c.core_types.bool.const_(1, false);
+
+                for field in &struct_info.fields {
+                    let lhs_field = builder
+                        .build_extract_value(lhs, field.llvm_idx, c"")
+                        .unwrap();
+                    let rhs_field = builder
+                        .build_extract_value(rhs, field.llvm_idx, c"")
+                        .unwrap();
+
+                    let eq = self.generate_equality(builder, &field.ty, lhs_field, rhs_field);
+                    result = builder.build_and(result, eq, c"");
+                }
+
+                builder.build_ret(*result);
+            },
+        )
+    }
+
+    /// Gets the lengths and data pointers of the two `str` parameters of a comparison function
+    fn str_params(
+        &self,
+        builder: &Builder<'ctx>,
+        function: FnValue<'ctx>,
+    ) -> (
+        IntValue<'ctx>,
+        wllvm::value::PtrValue<'ctx>,
+        IntValue<'ctx>,
+        wllvm::value::PtrValue<'ctx>,
+    ) {
+        let lhs = StructValue::try_from(function.param(0).unwrap()).unwrap();
+        let rhs = StructValue::try_from(function.param(1).unwrap()).unwrap();
+
+        let field = |val, idx| builder.build_extract_value(val, idx, c"").unwrap();
+
+        (
+            IntValue::try_from(field(lhs, 1)).unwrap(),
+            field(lhs, 0).try_into().unwrap(),
+            IntValue::try_from(field(rhs, 1)).unwrap(),
+            field(rhs, 0).try_into().unwrap(),
+        )
+    }
+
+    fn load_byte(
+        &self,
+        builder: &Builder<'ctx>,
+        ptr: wllvm::value::PtrValue<'ctx>,
+        idx: IntValue<'ctx>,
+    ) -> IntValue<'ctx> {
+        let i8 = self.c.context.int_type(8);
+        let byte_ptr = builder.build_gep(*i8, ptr, &[idx], c"");
+
+        IntValue::try_from(builder.build_load(*i8, byte_ptr, c"")).unwrap()
+    }
+}
@@ -19,6 +19,7 @@ struct DebugPrimitives<'ctx> {
     pub str: DIBasicType<'ctx>,
     pub unit: DIBasicType<'ctx>,
     pub bool: DIBasicType<'ctx>,
+    pub char: DIBasicType<'ctx>,
     pub never: DIBasicType<'ctx>,
     int_types: SharedBinarySearchMap<u32, DIBasicType<'ctx>>,
 }
@@ -28,6 +29,7 @@ pub struct DebugContext<'ctx> {
     pub cu: DICompileUnit<'ctx>,
     primitives: DebugPrimitives<'ctx>,
     structs: SharedBinarySearchMap<String, DIType<'ctx>>,
+    slices: SharedBinarySearchMap<String, DIType<'ctx>>,
     files: SharedBinarySearchMap<usize, DIFile<'ctx>>,
 }
 
@@ -62,6 +64,7 @@ impl<'ctx> DebugContext<'ctx> {
             cu,
             primitives,
             structs: BinarySearchMap::new().into(),
+            slices: BinarySearchMap::new().into(),
             files: files.into(),
         }
     }
@@ -82,6 +85,19 @@ impl<'ctx> DebugContext<'ctx> {
             Type::str => *self.primitives.str,
             Type::unit => *self.primitives.unit,
             Type::bool => *self.primitives.bool,
+            Type::char => *self.primitives.char,
+            Type::Slice(_) => {
+                let name = type_.to_string();
+
+                self.slices.get_or_insert_with(&name, || {
+                    *self.builder.basic_type(
+                        &name,
+                        u64::from(2 * cu.c.target_data.ptr_size() * 8),
+                        None,
+                        DIFlags::Private,
+                    )
+                })
+            }
             Type::never => *self.primitives.never,
             Type::Struct { ref path } => {
                 if let Some(ty) = self.structs.get(path) {
@@ -111,10 +127,17 @@ impl<'ctx> DebugContext<'ctx> {
 
                 let mut member_types = Vec::new();
 
-                for (i, FieldInfo { name, ty, line_no }) in struct_info.fields.iter().enumerate() {
+                for FieldInfo {
+                    name,
+                    ty,
+                    line_no,
+                    llvm_idx,
+                    ..
+                } in &struct_info.fields
+                {
                     let llvm_field_ty = ty.llvm_type(cu.c).unwrap();
                     let size = llvm_field_ty.size_bits(&cu.c.target_data);
-                    let align = llvm_field_ty.alignment(&cu.c.target_data);
+                    let align = ty.alignment(cu.c);
 
                     let dbg_field_ty = ty.get_dwarf_type(cu);
 
@@ -125,14 +148,14 @@ impl<'ctx> DebugContext<'ctx> {
                         *line_no,
                         size,
                         align * 8,
-                        llvm_ty.offset_of(&cu.c.target_data, i as u32) * 8,
+                        llvm_ty.offset_of(&cu.c.target_data, *llvm_idx) * 8,
                         DIFlags::Zero,
                         dbg_field_ty,
                     ));
                 }
 
                 let size_bits = llvm_ty.size_bits(&cu.c.target_data);
-                let align_bits = llvm_ty.alignment(&cu.c.target_data) * 8;
+                let align_bits = struct_info.alignment * 8;
 
                 let struct_type = self.builder.struct_type(
                     *self.cu,
@@ -179,11 +202,13 @@ impl<'ctx> DebugPrimitives<'ctx> {
         let unit = builder.basic_type("unit", 0, None, DIFlags::Private);
         let never = builder.basic_type("!", 0, None, DIFlags::Private);
         let bool = builder.basic_type("bool", 1, Some(TypeEncoding::boolean), DIFlags::Private);
+        let char = builder.basic_type("char", 32, Some(TypeEncoding::UTF), DIFlags::Private);
 
         Self {
             str,
             unit,
             bool,
+            char,
             int_types: BinarySearchMap::new().into(),
             never,
         }
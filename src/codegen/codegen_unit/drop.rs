@@ -0,0 +1,117 @@
+use std::ffi::CString;
+
+use wllvm::value::{Linkage, PtrValue, StructValue};
+
+use crate::{
+    codegen::{
+        codegen_unit::{moves, CodegenUnit},
+        types::Type,
+        values::{GenericValue, RValue},
+    },
+    parser::ast,
+};
+
+impl<'ctx> CodegenUnit<'_, 'ctx> {
+    /// Creates a drop flag for a new variable. The flag is set until the value of the variable is
+    /// moved out.
+    pub(crate) fn generate_drop_flag(&self) -> PtrValue<'ctx> {
+        let bool = self.c.core_types.bool;
+
+        let drop_flag = self.build_entry_alloca(&self.builder, *bool);
+        self.builder.build_store(*bool.const_(1, false), drop_flag);
+
+        drop_flag
+    }
+
+    /// Drops the value of an expression that isn't used any further. Values read from a variable
+    /// are still owned by it, so only temporaries are dropped.
+    pub(crate) fn drop_temporary(&self, expression: &ast::Expression, value: &RValue<'ctx>) {
+        if let Some(val) = value.val {
+            if !moves::is_place(expression) {
+                self.generate_drop(val, &value.type_);
+            }
+        }
+    }
+
+    /// Drops the value of a variable if its drop flag is still set
+    pub(crate) fn generate_flagged_drop(
+        &self,
+        value: &GenericValue<'ctx>,
+        drop_flag: PtrValue<'ctx>,
+    ) {
+        let current_block = self.builder.current_block().unwrap();
+        let drop_block = self.c.context.insert_basic_block_after(current_block, c"");
+        let continue_block = self.c.context.insert_basic_block_after(drop_block, c"");
+
+        let flag = self
+            .builder
+            .build_load(*self.c.core_types.bool, drop_flag, c"");
+        self.builder
+            .build_cond_br(flag.try_into().unwrap(), drop_block, continue_block);
+
+        self.builder.position_at_end(drop_block);
+
+        let value = value.clone().into_rvalue(self);
+        self.generate_drop(value.val.unwrap(), &value.type_);
+        self.builder.build_br(continue_block);
+
+        self.builder.position_at_end(continue_block);
+    }
+
+    /// Drops `val`. If it is a struct with a drop function, the function is called first, then
+    /// the fields of the struct are dropped in declaration order.
+    pub(crate) fn generate_drop(&self, val: wllvm::Value<'ctx>, type_: &Type) {
+        if !type_.needs_drop(self.c) {
+            return;
+        }
+
+        let Type::Struct { path } = type_ else {
+            unreachable!()
+        };
+
+        let struct_info = self
+            .c
+            .name_store
+            .get_item_from_string(path)
+            .unwrap()
+            .as_struct()
+            .unwrap();
+
+        if let Some(drop_fn) = &struct_info.drop_fn {
+            let function = self
+                .c
+                .name_store
+                .get_item_from_string(drop_fn)
+                .unwrap()
+                .as_function()
+                .unwrap();
+
+            let fn_name = function.function.name();
+
+            let mod_function = self.module.get_function(fn_name).unwrap_or_else(|| {
+                let func = self
+                    .module
+                    .add_function(&CString::new(fn_name).unwrap(), function.function.type_());
+                func.set_linkage(Linkage::External);
+                func
+            });
+
+            self.builder.build_fn_call(mod_function, &[val], c"");
+        }
+
+        let val = StructValue::try_from(val).unwrap();
+
+        for field in &struct_info.fields {
+            if !field.ty.needs_drop(self.c) {
+                continue;
+            }
+
+            let field_val = self
+                .builder
+                .build_extract_value(val, field.llvm_idx, c"")
+                .unwrap();
+
+            self.generate_drop(field_val, &field.ty);
+        }
+    }
+}
@@ -2,6 +2,7 @@ use crate::{
     codegen::{
         self,
         codegen_unit::CodegenUnit,
+        const_value::{self, ConstValue},
         error,
         scope::Scope,
         types::Type,
@@ -9,6 +10,7 @@ use crate::{
         warning,
     },
     error_handling::{self, Diagnostic, Spanned as S},
+    lexer::Number,
     parser::ast::{self, Expression, Literal, Path, Visibility},
     util,
 };
@@ -16,7 +18,9 @@ use crate::{
 use wllvm::value::Linkage;
 use wutil::Span;
 
+mod asm;
 mod control_flow;
+mod slice;
 mod struct_;
 
 impl<'ctx> CodegenUnit<'_, 'ctx> {
@@ -49,15 +53,24 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
                         return Err(codegen::error::undefined_variable(S(ident, expression.1)));
                     };
 
-                    Ok(var.value.clone().into_rvalue(self))
+                    let value = var.value.clone().into_rvalue(self);
+                    self.record_use(scope, S(ident, expression.1));
+
+                    Ok(value)
                 }
             },
             Expression::Literal(lit) => self.generate_literal(S(lit, expression.1)),
             Expression::BinaryOperator(a_expr, operator, b_expr) => {
                 let a = self.generate_rvalue(a_expr.as_sref(), scope)?;
-                let b = self.generate_rvalue(b_expr.as_sref(), scope)?;
+                let b = S(self.generate_rvalue(b_expr.as_sref(), scope)?, b_expr.1);
+
+                let result = a.generate_operation(&self, a_expr.1, *operator, &b)?;
+
+                // Operators only read their operands
+                self.drop_temporary(a_expr, &a);
+                self.drop_temporary(b_expr, &b);
 
-                a.generate_operation(&self, a_expr.1, *operator, &S(b, b_expr.1))
+                Ok(result)
             }
             Expression::CompoundExpression(block) => {
                 let mut scope = Scope::new(self, scope, expression.1.start);
@@ -72,16 +85,24 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
                 else_block,
             } => self.generate_if(scope, condition, block.as_sref(), else_block),
             Expression::Loop(block) => self.generate_loop(scope, block.as_sref()),
-            Expression::StructInitializer { name, fields } => {
-                self.generate_struct(scope, name, fields)
+            Expression::StructInitializer { name, fields, base } => {
+                self.generate_struct(scope, name, fields, base.as_deref())
             }
             Expression::FieldAccess(lhs, field) => self.generate_field_access(scope, lhs, field),
+            Expression::Index(slice, index) => self.generate_index(scope, slice, index),
+            Expression::Cast(value, type_) => {
+                let target = Type::new(self.c, self.crate_name, type_)?;
+                let rvalue = self.generate_rvalue(value.as_sref(), scope)?;
+
+                rvalue.generate_cast(self, value.1, S(&target, type_.1))
+            }
             Expression::Break(value) => {
                 self.generate_break(scope, value.as_ref().map(|v| v.as_sref()), expression.1)
             }
             Expression::Return(_val) => {
                 self.generate_return(scope, _val.as_ref().map(|v| v.as_sref()), expression.1)
             }
+            Expression::InlineAsm(asm) => self.generate_inline_asm(scope, asm),
         }
     }
 
@@ -127,12 +148,28 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
 
     fn generate_literal(&self, literal: S<&ast::Literal>) -> Result<RValue<'ctx>, Diagnostic> {
         match *literal {
-            Literal::Number(num) => self.generate_number_literal(num, literal.1),
+            Literal::Number(number) => self.generate_number_literal(number, literal.1),
             Literal::String(str) => Ok(self.generate_string_literal(str)),
+            Literal::ByteString(bytes) => Ok(RValue {
+                val: Some(self.generate_byte_slice(bytes)),
+                type_: Type::bytes(),
+            }),
+            Literal::Char(char) => Ok(RValue {
+                val: Some(*self.c.core_types.char.const_(u64::from(*char), false)),
+                type_: Type::char,
+            }),
         }
     }
 
     fn generate_string_literal(&self, lit: &str) -> RValue<'ctx> {
+        RValue {
+            val: Some(self.generate_byte_slice(lit.as_bytes())),
+            type_: Type::str,
+        }
+    }
+
+    /// Creates a constant global containing `lit` and returns a `str`/`[i8]` value pointing to it
+    fn generate_byte_slice(&self, lit: &[u8]) -> wllvm::Value<'ctx> {
         let string = self.c.context.const_string(lit, false);
 
         let string_global = self.module.add_global(
@@ -147,40 +184,66 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
         let string_ptr = string_global.as_ptr();
         let str_len = self.c.core_types.isize.const_(lit.len() as u64, false);
 
-        RValue {
-            val: Some(*self.c.core_types.str.const_(&[*string_ptr, *str_len])),
-            type_: Type::str,
-        }
+        *self.c.core_types.str.const_(&[*string_ptr, *str_len])
     }
 
-    fn generate_number_literal(&self, lit: &str, span: Span) -> Result<RValue<'ctx>, Diagnostic> {
-        let idx = lit.find(|c: char| !c.is_ascii_digit()).unwrap_or(lit.len());
-        let suffix = &lit[idx..];
-
-        let type_ = if suffix.is_empty() {
-            Type::i(32)
-        } else {
-            Type::i(
-                suffix
-                    .strip_prefix("i")
-                    .and_then(|s| s.parse::<u32>().ok())
-                    .ok_or_else(|| codegen::error::invalid_number(S(lit, span)))?,
-            )
-        };
+    fn generate_number_literal(
+        &self,
+        number: &Number,
+        span: Span,
+    ) -> Result<RValue<'ctx>, Diagnostic> {
+        let (bits, words) = const_value::number_literal(number, span)?;
 
         Ok(RValue {
             val: Some(
                 *self
                     .c
                     .context
-                    .int_type(32)
-                    .const_from_string(&lit[..idx], 10)
-                    .ok_or_else(|| codegen::error::invalid_number(S(lit, span)))?,
+                    .int_type(bits)
+                    .const_arbitrary_precision(&words),
             ),
-            type_,
+            type_: Type::i(bits),
         })
     }
 
+    /// Creates an LLVM constant from a compile-time value
+    pub(crate) fn generate_const_value(&self, value: &ConstValue) -> wllvm::Value<'ctx> {
+        match value {
+            ConstValue::Int { bits, words } => *self
+                .c
+                .context
+                .int_type(*bits)
+                .const_arbitrary_precision(words),
+            ConstValue::Bool(bool) => *self.c.core_types.bool.const_(u64::from(*bool), false),
+            ConstValue::Char(char) => *self.c.core_types.char.const_(u64::from(*char), false),
+            ConstValue::Str(str) => self.generate_byte_slice(str.as_bytes()),
+            ConstValue::Bytes(bytes) => self.generate_byte_slice(bytes),
+            ConstValue::Struct { path, fields } => {
+                let struct_info = self
+                    .c
+                    .name_store
+                    .get_item_from_string(path)
+                    .unwrap()
+                    .as_struct()
+                    .unwrap();
+
+                let llvm_type = struct_info.llvm_type.unwrap();
+                let mut val = llvm_type.const_null();
+
+                for (field, field_val) in struct_info.fields.iter().zip(fields) {
+                    val = self.builder.build_insert_value(
+                        val,
+                        self.generate_const_value(field_val),
+                        field.llvm_idx,
+                        c"",
+                    );
+                }
+
+                *val
+            }
+        }
+    }
+
     fn generate_function_call(
         &self,
         span: Span,
@@ -196,15 +259,14 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
                 .ok_or_else(|| codegen::error::not_function(*fn_name))?
                 .clone()
         } else if let [parent_crate, fn_direct_name] = &***fn_name {
-            let func = self
-                .c
-                .name_store
-                .get_item(fn_name)?
+            let item = self.c.name_store.get_item(fn_name)?;
+
+            let func = item
                 .as_function()
                 .ok_or_else(|| codegen::error::not_function_path(fn_name))?
                 .clone();
 
-            if func.visibility != Visibility::Public && **parent_crate != self.crate_name {
+            if item.visibility() == Some(Visibility::Private) && **parent_crate != self.crate_name {
                 return Err(codegen::error::private_function(
                     *parent_crate,
                     *fn_direct_name,
@@ -223,6 +285,11 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
             let func = self.module.add_function(c"", function.function.type_());
             func.set_name(fn_name);
             func.set_linkage(Linkage::External);
+
+            if function.c_abi {
+                self.c.add_c_attributes(func, &function.signature);
+            }
+
             func
         });
 
@@ -241,13 +308,13 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
 
         for (i, arg) in arguments.iter().enumerate() {
             let arg_span = arg.1;
-            let arg = self.generate_rvalue(arg.as_sref(), scope)?;
+            let arg = self.generate_owned_rvalue(arg.as_sref(), scope)?;
 
             let expected_type = &signature.params[i];
             if !arg.type_.is(expected_type) {
                 return Err(codegen::error::unexpected_type(
                     arguments[i].1,
-                    expected_type,
+                    &signature.param_names[i],
                     &arg.type_,
                 ));
             }
@@ -275,9 +342,12 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
             });
         }
 
-        let ret_val = self
-            .builder
-            .build_fn_call(mod_function, &metadata_arguments, c"");
+        let ret_val = if function.c_abi {
+            self.generate_c_call(mod_function, &function.signature, &metadata_arguments)
+        } else {
+            self.builder
+                .build_fn_call(mod_function, &metadata_arguments, c"")
+        };
 
         if function.signature.return_type.llvm_type(self.c).is_none() {
             return Ok(RValue {
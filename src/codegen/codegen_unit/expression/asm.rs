@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use wllvm::{type_::AsmDialect, value::StructValue};
+
+use crate::{
+    codegen::{
+        codegen_unit::CodegenUnit,
+        error,
+        scope::Scope,
+        types::Type,
+        values::{MutValue, RValue},
+    },
+    error_handling::{Diagnostic, Spanned as S},
+    parser::ast::{AsmDirection, Expression, InlineAsm},
+};
+
+/// The registers that can be used as operands or clobbers. The stack and frame pointers are
+/// managed by LLVM, so they cannot be used.
+const REGISTERS: &[&str] = &[
+    "rax", "rbx", "rcx", "rdx", "rsi", "rdi", "r8", "r9", "r10", "r11", "r12", "r13", "r14", "r15",
+];
+
+impl<'ctx> CodegenUnit<'_, 'ctx> {
+    /// Generates an inline assembly expression. Inputs are evaluated before the assembly runs, and
+    /// outputs are written to their places afterwards.
+    pub(crate) fn generate_inline_asm(
+        &self,
+        scope: &mut Scope<'_, 'ctx>,
+        asm: &InlineAsm,
+    ) -> Result<RValue<'ctx>, Diagnostic> {
+        // The registers that are read and written by the operands along with where they are used.
+        // A register can be both an input and an output, but not two inputs or two outputs.
+        let mut input_registers = HashMap::new();
+        let mut output_registers = HashMap::new();
+
+        for operand in &asm.operands {
+            let register = S(operand.register.as_str(), operand.register.1);
+
+            if !REGISTERS.contains(&*register) {
+                return Err(error::invalid_asm_register(register));
+            }
+
+            if operand.direction != AsmDirection::Out {
+                if let Some(previous) = input_registers.insert(*register, register.1) {
+                    return Err(error::duplicate_asm_register(register, previous));
+                }
+            }
+
+            if operand.direction != AsmDirection::In {
+                if let Some(previous) = output_registers.insert(*register, register.1) {
+                    return Err(error::duplicate_asm_register(register, previous));
+                }
+            }
+        }
+
+        for clobber in &asm.clobbers {
+            let clobber = S(clobber.as_str(), clobber.1);
+
+            if *clobber != "memory" && !REGISTERS.contains(&*clobber) {
+                return Err(error::invalid_asm_register(clobber));
+            }
+
+            if let Some(&output) = output_registers.get(*clobber) {
+                return Err(error::clobbered_asm_output(clobber, output));
+            }
+        }
+
+        let mut inputs = Vec::new();
+        let mut input_constraints = Vec::new();
+
+        let mut outputs: Vec<(MutValue, &S<Expression>)> = Vec::new();
+        let mut output_constraints = Vec::new();
+
+        let mut unreachable = false;
+
+        for operand in &asm.operands {
+            let expression = &operand.expression;
+            let register = &*operand.register;
+
+            if operand.direction != AsmDirection::Out {
+                let input = self.generate_rvalue(expression.as_sref(), scope)?;
+                check_operand_type(expression.1, &input.type_)?;
+
+                let Some(input) = input.val else {
+                    unreachable = true;
+                    continue;
+                };
+
+                inputs.push(input);
+
+                // The input of an `inout` operand is tied to its output
+                if operand.direction == AsmDirection::InOut {
+                    input_constraints.push(outputs.len().to_string());
+                } else {
+                    input_constraints.push(format!("{{{register}}}"));
+                }
+            }
+
+            if operand.direction != AsmDirection::In {
+                let output = self.generate_mutvalue(expression.as_sref(), scope)?;
+                check_operand_type(expression.1, &output.type_)?;
+
+                if output.ptr.is_none() {
+                    unreachable = true;
+                    continue;
+                }
+
+                outputs.push((output, expression));
+                output_constraints.push(format!("={{{register}}}"));
+            }
+        }
+
+        if unreachable {
+            return Ok(RValue {
+                val: None,
+                type_: Type::never,
+            });
+        }
+
+        let clobber_constraints = asm
+            .clobbers
+            .iter()
+            .map(|clobber| format!("~{{{}}}", **clobber))
+            // Like in C, the flags are always assumed to be clobbered
+            .chain(["~{dirflag}", "~{fpsr}", "~{flags}"].map(String::from));
+
+        let constraints: Vec<String> = output_constraints
+            .into_iter()
+            .chain(input_constraints)
+            .chain(clobber_constraints)
+            .collect();
+
+        let output_types: Vec<wllvm::Type> = outputs
+            .iter()
+            .map(|(output, _)| output.type_.llvm_type(self.c).unwrap())
+            .collect();
+
+        let return_type = match *output_types {
+            [] => *self.c.context.void_type(),
+            [output_type] => output_type,
+            _ => *self.c.context.struct_type(&output_types, false),
+        };
+
+        let input_types: Vec<wllvm::Type> = inputs.iter().map(|input| input.type_()).collect();
+        let asm_type = self.c.context.fn_type(return_type, &input_types, false);
+
+        // `$` starts an operand reference in LLVM templates, but operands are accessed through
+        // their registers, so it is escaped to allow AT&T immediates such as `$1`
+        let template = asm
+            .template
+            .iter()
+            .map(|line| line.replace('$', "$$"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let asm_fn = asm_type.inline_asm(
+            &template,
+            &constraints.join(","),
+            asm.volatile,
+            false,
+            AsmDialect::ATT,
+            false,
+        );
+
+        let result = self.builder.build_ptr_call(asm_type, asm_fn, &inputs, c"");
+
+        for (i, (output, expression)) in outputs.iter().enumerate() {
+            let value = if outputs.len() == 1 {
+                result
+            } else {
+                self.builder
+                    .build_extract_value(StructValue::try_from(result).unwrap(), i as u32, c"")
+                    .unwrap()
+            };
+
+            self.builder.build_store(value, output.ptr.unwrap());
+
+            if let Expression::Identifier(name) = ***expression {
+                self.reinitialize_variable(scope, name);
+            }
+        }
+
+        Ok(RValue {
+            val: Some(*self.c.core_types.unit.const_(&[])),
+            type_: Type::unit,
+        })
+    }
+}
+
+/// Checks that a value of `type_` fits in a general-purpose register
+fn check_operand_type(span: wutil::Span, type_: &Type) -> Result<(), Diagnostic> {
+    match type_ {
+        Type::i(bits) if *bits <= 64 => Ok(()),
+        Type::char | Type::never => Ok(()),
+        _ => Err(error::invalid_asm_operand_type(span, type_)),
+    }
+}
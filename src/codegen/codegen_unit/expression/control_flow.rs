@@ -6,7 +6,7 @@ use crate::{
         self,
         codegen_unit::CodegenUnit,
         error,
-        scope::{BreakContext, Scope},
+        scope::{BreakContext, Scope, ScopeExit},
         types::Type,
         values::RValue,
         warning,
@@ -28,7 +28,13 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
             return Err(error::break_outside_of_loop(span));
         };
 
-        let value = value.map(|v| self.generate_rvalue(v, scope)).transpose()?;
+        let value = value
+            .map(|v| self.generate_owned_rvalue(v, scope))
+            .transpose()?;
+
+        if value.as_ref().is_none_or(|v| v.val.is_some()) {
+            scope.generate_drops(self, ScopeExit::Break);
+        }
 
         break_context.build_break(&self, value, vspan)?;
 
@@ -52,24 +58,28 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
     ) -> Result<RValue<'ctx>, Diagnostic> {
         let value_span = value.map_or(span, |S(_, s)| s);
 
-        let rvalue = value.map(|v| self.generate_rvalue(v, scope)).transpose()?;
+        let rvalue = value
+            .map(|v| self.generate_owned_rvalue(v, scope))
+            .transpose()?;
         let rvalue = rvalue.unwrap_or_else(|| RValue {
             val: Some(*self.c.context.const_struct(&[], false)),
             type_: Type::unit,
         });
 
-        let Some(return_type) = scope.get_return_type() else {
+        let Some((return_type, return_type_name)) = scope.get_return_type() else {
             return Err(error::return_outside_of_function(span));
         };
 
         if !rvalue.type_.is(return_type) {
             return Err(error::incorrect_explicit_return_type(
-                return_type,
+                return_type_name,
                 S(&rvalue.type_, value_span),
             ));
         }
 
         if let Some(val) = rvalue.val {
+            scope.generate_drops(self, ScopeExit::Return);
+
             self.builder.build_ret(val);
         } else {
             self.builder.build_unreachable();
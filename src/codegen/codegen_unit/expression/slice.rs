@@ -0,0 +1,134 @@
+use wllvm::{
+    builder::IntPredicate,
+    value::{IntValue, StructValue},
+};
+
+use crate::{
+    codegen::{codegen_unit::CodegenUnit, error, scope::Scope, types::Type, values::RValue},
+    error_handling::{Diagnostic, Spanned as S},
+    parser::ast::Expression,
+};
+
+impl<'ctx> CodegenUnit<'_, 'ctx> {
+    /// Generates `slice[index]`. If the index is out of bounds, the program is aborted
+    pub(crate) fn generate_index(
+        &self,
+        scope: &mut Scope<'_, 'ctx>,
+        slice: &S<Expression>,
+        index: &S<Expression>,
+    ) -> Result<RValue<'ctx>, Diagnostic> {
+        let slice_span = slice.1;
+        let index_span = index.1;
+
+        let slice = self.generate_rvalue(slice.as_sref(), scope)?;
+        let index = self.generate_rvalue(index.as_sref(), scope)?;
+
+        let Type::Slice(element_type) = slice.type_ else {
+            return Err(error::non_slice_index(S(&slice.type_, slice_span)));
+        };
+
+        let Type::i(index_bits) = index.type_ else {
+            return Err(error::non_integer_index(S(&index.type_, index_span)));
+        };
+
+        let element_type = *element_type;
+
+        let (Some(slice), Some(index), Some(llvm_element_type)) =
+            (slice.val, index.val, element_type.llvm_type(self.c))
+        else {
+            return Ok(RValue {
+                val: None,
+                type_: element_type,
+            });
+        };
+
+        let isize = self.c.core_types.isize;
+        let index = IntValue::try_from(index).unwrap();
+
+        let (ptr, len) = self.slice_parts(StructValue::try_from(slice).unwrap());
+
+        // Negative indices are sign-extended so that they are out of bounds. Indices wider than
+        // `isize` are checked before they are truncated so that they cannot wrap into bounds.
+        let (index, in_bounds) = if index_bits <= isize.width() {
+            let index = if index_bits < isize.width() {
+                self.builder.build_sext(index, isize, c"")
+            } else {
+                index
+            };
+
+            let in_bounds = self.builder.build_icmp(IntPredicate::ULT, index, len, c"");
+
+            (index, in_bounds)
+        } else {
+            let wide_len = self
+                .builder
+                .build_zext(len, self.c.context.int_type(index_bits), c"");
+            let in_bounds = self
+                .builder
+                .build_icmp(IntPredicate::ULT, index, wide_len, c"");
+
+            (self.builder.build_trunc(index, isize, c""), in_bounds)
+        };
+
+        self.generate_assertion(in_bounds);
+
+        let element_ptr =
+            self.builder
+                .build_gep(llvm_element_type, ptr.try_into().unwrap(), &[index], c"");
+
+        Ok(RValue {
+            val: Some(self.builder.build_load(llvm_element_type, element_ptr, c"")),
+            type_: element_type,
+        })
+    }
+
+    /// Generates `value.len` for a `str` or slice
+    pub(crate) fn generate_len(&self, value: &RValue<'ctx>) -> RValue<'ctx> {
+        let type_ = Type::i(self.c.core_types.isize.width());
+
+        let Some(val) = value.val else {
+            return RValue { val: None, type_ };
+        };
+
+        let (_, len) = self.slice_parts(StructValue::try_from(val).unwrap());
+
+        RValue {
+            val: Some(*len),
+            type_,
+        }
+    }
+
+    /// Splits a `str` or slice into its pointer and its length
+    fn slice_parts(&self, slice: StructValue<'ctx>) -> (wllvm::Value<'ctx>, IntValue<'ctx>) {
+        let ptr = self.builder.build_extract_value(slice, 0, c"").unwrap();
+        let len = self.builder.build_extract_value(slice, 1, c"").unwrap();
+
+        (ptr, IntValue::try_from(len).unwrap())
+    }
+
+    /// Aborts the program if `condition` is false
+    pub(crate) fn generate_assertion(&self, condition: IntValue<'ctx>) {
+        let current_block = self.builder.current_block().unwrap();
+        let fail_block = self.c.context.insert_basic_block_after(current_block, c"");
+        let continue_block = self.c.context.insert_basic_block_after(fail_block, c"");
+
+        self.builder
+            .build_cond_br(condition, continue_block, fail_block);
+
+        self.builder.position_at_end(fail_block);
+
+        let trap = self.module.get_function("llvm.trap").unwrap_or_else(|| {
+            self.module.add_function(
+                c"llvm.trap",
+                self.c
+                    .context
+                    .fn_type(*self.c.context.void_type(), &[], false),
+            )
+        });
+
+        self.builder.build_fn_call(trap, &[], c"");
+        self.builder.build_unreachable();
+
+        self.builder.position_at_end(continue_block);
+    }
+}
@@ -1,9 +1,9 @@
-use wllvm::{type_::StructType, value::StructValue};
+use wllvm::value::StructValue;
 
 use crate::{
     codegen::{
         self,
-        codegen_unit::CodegenUnit,
+        codegen_unit::{moves, CodegenUnit},
         error,
         scope::Scope,
         types::Type,
@@ -25,6 +25,10 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
         let lhs_span = lhs.1;
         let lhs = self.generate_rvalue(lhs.as_sref(), scope)?;
 
+        if matches!(lhs.type_, Type::str | Type::Slice(_)) && **field == "len" {
+            return Ok(self.generate_len(&lhs));
+        }
+
         let Type::Struct { path } = lhs.type_ else {
             return Err(codegen::error::non_struct_element_access(
                 lhs_span, &lhs.type_, field,
@@ -45,6 +49,8 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
             .position(|fi| fi.name == **field)
             .ok_or_else(|| codegen::error::invalid_field(&path, *field))?;
 
+        struct_info.fields[idx].check_visibility(&path, self.crate_name, *field)?;
+
         let Some(lhs_val) = lhs.val else {
             return Ok(RValue {
                 val: None,
@@ -58,7 +64,7 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
 
         let val = self
             .builder
-            .build_extract_value(lhs, idx as u32, c"")
+            .build_extract_value(lhs, struct_info.fields[idx].llvm_idx, c"")
             .unwrap();
 
         Ok(RValue {
@@ -74,7 +80,14 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
         field: &S<&str>,
     ) -> Result<MutValue<'ctx>, Diagnostic> {
         let lhs_span = lhs.1;
+        let lhs_expr = lhs;
         let lhs = self.generate_mutvalue(lhs.as_sref(), scope)?;
+
+        // Modifying a field reads the rest of the variable
+        if let Some(name) = moves::place_root(lhs_expr) {
+            self.record_use(scope, S(name, lhs_span));
+        }
+
         let Type::Struct { path } = &lhs.type_ else {
             return Err(error::non_struct_element_access(
                 lhs_span, &lhs.type_, field,
@@ -93,6 +106,8 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
             .position(|f| f.name == **field)
             .ok_or_else(|| error::invalid_field(path, *field))?;
 
+        struct_info.fields[idx].check_visibility(path, self.crate_name, *field)?;
+
         let Some((lhs_ptr, lhs_llvm_type)) = lhs.ptr.zip(lhs.type_.llvm_type(&self.c)) else {
             return Ok(MutValue {
                 ptr: None,
@@ -104,7 +119,10 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
         let field_ptr = self.builder.build_gep(
             lhs_llvm_type,
             lhs_ptr,
-            &[isize.const_(0, false), isize.const_(idx as u64, false)],
+            &[
+                isize.const_(0, false),
+                isize.const_(u64::from(struct_info.fields[idx].llvm_idx), false),
+            ],
             c"",
         );
 
@@ -119,6 +137,7 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
         scope: &mut Scope<'_, 'ctx>,
         name: &S<util::MaybeVec<S<&str>>>,
         fields: &Vec<S<ast::StructInitializerField>>,
+        base: Option<&S<Expression>>,
     ) -> Result<RValue<'ctx>, Diagnostic> {
         struct AssignedField<'a, 'ctx> {
             src_idx: usize,
@@ -156,7 +175,7 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
 
             let mut scope = Scope::new(self, scope, field.1.start);
             let value = S(
-                self.generate_rvalue(field.val.as_sref(), &mut scope)?,
+                self.generate_owned_rvalue(field.val.as_sref(), &mut scope)?,
                 field.val.1,
             );
 
@@ -173,18 +192,52 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
             );
         }
 
-        let mut field_values = Vec::<wllvm::Value>::new();
+        let base_expr = base;
+        let base = base
+            .map(|base| {
+                let mut scope = Scope::new(self, scope, base.1.start);
+                Ok(S(self.generate_rvalue(base.as_sref(), &mut scope)?, base.1))
+            })
+            .transpose()?;
+
+        if let Some(base) = &base {
+            if !base.type_.is(&type_) {
+                return Err(error::unexpected_type(base.1, &type_, &base.type_));
+            }
+        }
+
+        let mut field_values = Vec::<(u32, Option<wllvm::Value>)>::new();
         let mut first_diverging_src_idx: Option<usize> = None;
+        let mut moves_base = false;
 
         for field in &struct_info.fields {
             let assigned_val = match assigned_fields.binary_search_by(|v| v.name.cmp(&field.name)) {
                 Ok(idx) => &assigned_fields[idx],
-                Err(_) => return Err(error::missing_field(&field.name, S(path, name.1))),
+                Err(_) => {
+                    if let Some(base) = &base {
+                        field.check_visibility(path, self.crate_name, S(&field.name, base.1))?;
+
+                        // The value is extracted from the base struct later
+                        field_values.push((field.llvm_idx, None));
+                        moves_base |= !field.ty.is_copy(self.c);
+                        continue;
+                    }
+
+                    let Some(default) = &field.default else {
+                        return Err(error::missing_field(&field.name, S(path, name.1)));
+                    };
+
+                    let val = self.generate_const_value(default);
+                    field_values.push((field.llvm_idx, Some(val)));
+                    continue;
+                }
             };
 
+            field.check_visibility(path, self.crate_name, assigned_val.name)?;
+
             let val = &assigned_val.value;
             if !val.type_.is(&field.ty) {
-                return Err(error::unexpected_type(val.1, &field.ty, &val.type_));
+                return Err(error::unexpected_type(val.1, &field.type_name, &val.type_));
             }
 
             let Some(val) = val.val else {
@@ -196,7 +249,7 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
                 continue;
             };
 
-            field_values.push(val);
+            field_values.push((field.llvm_idx, Some(val)));
         }
 
         if struct_info.fields.len() != assigned_fields.len() {
@@ -207,8 +260,16 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
             }
         }
 
+        if let Some((base_expr, base)) = base_expr.zip(base.as_ref()).filter(|_| moves_base) {
+            self.move_out(base_expr.as_sref(), base, scope)?;
+        }
+
         if let Some(idx) = first_diverging_src_idx {
-            if let Some(dead_code) = error_handling::span_of(&fields[idx + 1..]) {
+            let dead_code = error_handling::span_of(&fields[idx + 1..])
+                .map(|span| base.as_ref().map_or(span, |b| (span.start..b.1.end).into()))
+                .or(base.as_ref().map(|b| b.1));
+
+            if let Some(dead_code) = dead_code {
                 self.c.warnings.push((
                     self.file_no,
                     warning::unreachable_code(fields[idx].val.1, dead_code),
@@ -218,10 +279,30 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
             return Ok(RValue { val: None, type_ });
         }
 
-        let llvm_type = StructType::try_from(type_.llvm_type(self.c).unwrap()).unwrap();
+        let base_val = match base.map(|b| b.0.val) {
+            Some(None) => return Ok(RValue { val: None, type_ }),
+            Some(Some(val)) => Some(StructValue::try_from(val).unwrap()),
+            None => None,
+        };
 
-        let val = Some(*llvm_type.const_(&field_values));
+        let llvm_type = type_.llvm_type(self.c).unwrap();
+        let mut val = StructValue::try_from(llvm_type.const_null()).unwrap();
 
-        Ok(RValue { val, type_ })
+        for (llvm_idx, field_val) in field_values {
+            let field_val = field_val.unwrap_or_else(|| {
+                self.builder
+                    .build_extract_value(base_val.unwrap(), llvm_idx, c"")
+                    .unwrap()
+            });
+
+            val = self
+                .builder
+                .build_insert_value(val, field_val, llvm_idx, c"");
+        }
+
+        Ok(RValue {
+            val: Some(*val),
+            type_,
+        })
     }
 }
@@ -0,0 +1,243 @@
+use std::ffi::CString;
+
+use wllvm::{
+    value::{FnValue, Linkage, PtrValue, StructValue},
+    Builder,
+};
+
+use crate::codegen::{
+    codegen_context::abi::PassMode,
+    namestore::{FunctionInfo, FunctionSignature},
+    types::Type,
+    CodegenUnit,
+};
+
+impl<'ctx> CodegenUnit<'_, 'ctx> {
+    /// Calls a function that uses the C calling convention. The arguments are converted to the
+    /// types that the function takes, and the return value is converted back to its wlang type.
+    pub(crate) fn generate_c_call(
+        &self,
+        function: FnValue<'ctx>,
+        signature: &FunctionSignature,
+        args: &[wllvm::Value<'ctx>],
+    ) -> wllvm::Value<'ctx> {
+        let builder = &self.builder;
+        let c_signature = self.c.c_signature(signature);
+
+        let mut c_args = Vec::new();
+
+        let return_ptr = if c_signature.return_ == PassMode::Indirect {
+            let ptr = self.build_abi_alloca(builder, &signature.return_type, None);
+            c_args.push(*ptr);
+
+            Some(ptr)
+        } else {
+            None
+        };
+
+        for ((&arg, type_), pass_mode) in
+            args.iter().zip(&signature.params).zip(&c_signature.params)
+        {
+            match pass_mode {
+                PassMode::Direct => c_args.push(arg),
+                PassMode::Ignore => {}
+                PassMode::Cast(widths) => {
+                    let cast_type = self.c.cast_type(widths);
+                    let ptr = self.build_abi_alloca(builder, type_, Some(cast_type));
+
+                    builder.build_store(arg, ptr);
+                    let cast = builder.build_load(cast_type, ptr, c"");
+
+                    c_args.extend(split_eightbytes(builder, cast, widths.len()));
+                }
+                PassMode::Indirect => {
+                    let ptr = self.build_abi_alloca(builder, type_, None);
+                    builder.build_store(arg, ptr);
+
+                    c_args.push(*ptr);
+                }
+            }
+        }
+
+        let ret = builder.build_fn_call(function, &c_args, c"");
+
+        let return_type = &signature.return_type;
+
+        match c_signature.return_ {
+            PassMode::Direct => ret,
+            PassMode::Ignore => return_type
+                .llvm_type(self.c)
+                .map_or(ret, |llvm_type| llvm_type.const_null()),
+            PassMode::Cast(widths) => {
+                let cast_type = self.c.cast_type(&widths);
+                let ptr = self.build_abi_alloca(builder, return_type, Some(cast_type));
+
+                builder.build_store(ret, ptr);
+                builder.build_load(return_type.llvm_type(self.c).unwrap(), ptr, c"")
+            }
+            PassMode::Indirect => builder.build_load(
+                return_type.llvm_type(self.c).unwrap(),
+                return_ptr.unwrap(),
+                c"",
+            ),
+        }
+    }
+
+    /// Generates a function called `name` that uses the C calling convention and forwards its
+    /// arguments to `function_info`. This is how `#[no_mangle]` functions are exported. If the
+    /// module already declares `name` (eg as an `extern fn`), that declaration is defined instead.
+    pub(crate) fn generate_c_export(
+        &self,
+        name: &str,
+        function_info: &FunctionInfo<'ctx>,
+    ) -> FnValue<'ctx> {
+        let signature = &function_info.signature;
+        let c_signature = self.c.c_signature(signature);
+
+        let export = self.module.get_function(name).unwrap_or_else(|| {
+            let export = self
+                .module
+                .add_function(&CString::new(name).unwrap(), self.c.c_fn_type(signature));
+            self.c.add_c_attributes(export, signature);
+
+            export
+        });
+        export.set_linkage(Linkage::External);
+
+        // A separate builder is used so that the position and debug location of the main builder
+        // are left untouched
+        let builder = self.c.context.create_builder();
+        builder.position_at_end(export.add_basic_block(c""));
+
+        if signature
+            .params
+            .iter()
+            .any(|param| param.llvm_type(self.c).is_none())
+        {
+            builder.build_unreachable();
+            return export;
+        }
+
+        let mut c_params = (0..export.num_params()).map(|i| export.param(i).unwrap());
+
+        let return_ptr = if c_signature.return_ == PassMode::Indirect {
+            Some(PtrValue::try_from(c_params.next().unwrap()).unwrap())
+        } else {
+            None
+        };
+
+        let mut args = Vec::new();
+
+        for (type_, pass_mode) in signature.params.iter().zip(&c_signature.params) {
+            let llvm_type = type_.llvm_type(self.c).unwrap();
+
+            let arg = match pass_mode {
+                PassMode::Direct => c_params.next().unwrap(),
+                PassMode::Ignore => llvm_type.const_null(),
+                PassMode::Cast(widths) => {
+                    let cast_type = self.c.cast_type(widths);
+                    let eightbytes: Vec<_> = c_params.by_ref().take(widths.len()).collect();
+                    let cast = join_eightbytes(&builder, cast_type, &eightbytes);
+
+                    let ptr = self.build_abi_alloca(&builder, type_, Some(cast_type));
+                    builder.build_store(cast, ptr);
+
+                    builder.build_load(llvm_type, ptr, c"")
+                }
+                PassMode::Indirect => {
+                    let ptr = PtrValue::try_from(c_params.next().unwrap()).unwrap();
+
+                    builder.build_load(llvm_type, ptr, c"")
+                }
+            };
+
+            args.push(arg);
+        }
+
+        let ret = builder.build_fn_call(function_info.function, &args, c"");
+
+        let return_type = &signature.return_type;
+
+        match c_signature.return_ {
+            PassMode::Direct => builder.build_ret(ret),
+            PassMode::Ignore if return_type.llvm_type(self.c).is_none() => {
+                builder.build_unreachable();
+            }
+            PassMode::Ignore => builder.build_ret_void(),
+            PassMode::Cast(widths) => {
+                let cast_type = self.c.cast_type(&widths);
+                let ptr = self.build_abi_alloca(&builder, return_type, Some(cast_type));
+
+                builder.build_store(ret, ptr);
+                builder.build_ret(builder.build_load(cast_type, ptr, c""));
+            }
+            PassMode::Indirect => {
+                builder.build_store(ret, return_ptr.unwrap());
+                builder.build_ret_void();
+            }
+        }
+
+        export
+    }
+
+    /// Allocates stack space for a value of `type_` that is passed in memory or converted to
+    /// `cast_type` through memory. Values in memory are aligned to at least eight bytes.
+    fn build_abi_alloca(
+        &self,
+        builder: &Builder<'ctx>,
+        type_: &Type,
+        cast_type: Option<wllvm::Type<'ctx>>,
+    ) -> PtrValue<'ctx> {
+        let mut alignment = type_.alignment(self.c).max(8);
+
+        // The cast type is never smaller than the value, so it is allocated instead
+        let alloca_type = if let Some(cast_type) = cast_type {
+            alignment = alignment.max(cast_type.alignment(&self.c.target_data));
+            cast_type
+        } else {
+            type_.llvm_type(self.c).unwrap()
+        };
+
+        let ptr = self.build_entry_alloca(builder, alloca_type);
+        ptr.set_alignment(alignment);
+
+        ptr
+    }
+}
+
+/// Splits a value that was converted to a cast type (see `CodegenContext::cast_type`) into its
+/// eightbytes
+fn split_eightbytes<'ctx>(
+    builder: &Builder<'ctx>,
+    cast: wllvm::Value<'ctx>,
+    count: usize,
+) -> Vec<wllvm::Value<'ctx>> {
+    if count == 1 {
+        return vec![cast];
+    }
+
+    let cast = StructValue::try_from(cast).unwrap();
+
+    (0..count as u32)
+        .map(|i| builder.build_extract_value(cast, i, c"").unwrap())
+        .collect()
+}
+
+/// The inverse of [`split_eightbytes`]
+fn join_eightbytes<'ctx>(
+    builder: &Builder<'ctx>,
+    cast_type: wllvm::Type<'ctx>,
+    eightbytes: &[wllvm::Value<'ctx>],
+) -> wllvm::Value<'ctx> {
+    let [eightbyte] = *eightbytes else {
+        let mut cast = StructValue::try_from(cast_type.const_null()).unwrap();
+
+        for (i, &eightbyte) in eightbytes.iter().enumerate() {
+            cast = builder.build_insert_value(cast, eightbyte, i as u32, c"");
+        }
+
+        return *cast;
+    };
+
+    eightbyte
+}
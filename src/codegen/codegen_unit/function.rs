@@ -1,14 +1,21 @@
 use crate::{
     codegen::{
-        self, namestore::NameStoreEntry, scope::Scope, types::Type, values::RValue, warning,
-        CodegenUnit,
+        self,
+        namestore::NameStoreEntry,
+        scope::{Scope, ScopeExit},
+        types::Type,
+        values::RValue,
+        warning, CodegenUnit,
     },
     error_handling::{self, Diagnostic, Spanned as S},
     parser::ast::{self, Attribute, Visibility},
     util,
 };
 
-use wllvm::debug_info::{DIFlags, DIType};
+use wllvm::{
+    debug_info::{DIFlags, DIType},
+    value::Linkage,
+};
 use wutil::Span;
 
 impl<'ctx> CodegenUnit<'_, 'ctx> {
@@ -27,6 +34,31 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
 
         let ll_function = function_info.function;
 
+        if function
+            .attributes
+            .iter()
+            .any(|attr| **attr == Attribute::NoMangle)
+        {
+            self.generate_c_export(function.name, function_info);
+        }
+
+        // `std::alloc` and `std::dealloc` call `__wlab_alloc` and `__wlab_dealloc`. Default
+        // allocators are weak so that they can be replaced by another crate
+        for attr in &function.attributes {
+            if let Attribute::GlobalAllocator(kind, default) = **attr {
+                let export = self.generate_c_export(&format!("__wlab_{kind}"), function_info);
+
+                if default {
+                    export.set_linkage(Linkage::WeakAny);
+                }
+            }
+        }
+
+        // `extern` functions are only declared
+        let Some(body) = &function.body else {
+            return Ok(());
+        };
+
         let params: Result<Vec<(S<&str>, Type)>, _> = function
             .params
             .iter()
@@ -55,7 +87,7 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
             di_flags,
         );
 
-        let (scope_line_no, scope_col_no) = util::line_and_col(self.source, function.body.1.start);
+        let (scope_line_no, scope_col_no) = util::line_and_col(self.source, body.1.start);
         let (fn_line_no, _) = util::line_and_col(self.source, function.1.start);
 
         let subprogram = self.debug_context.builder.subprogram(
@@ -74,7 +106,7 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
 
         ll_function.set_subprogram(subprogram);
 
-        let fn_scope = Scope::new_function(self, scope, *subprogram, function.body.1.start);
+        let fn_scope = Scope::new_function(self, scope, *subprogram, body.1.start);
 
         self.builder
             .set_debug_location(self.c.context.debug_location(
@@ -109,18 +141,26 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
         self.builder.position_at_end(main_block);
 
         let mut fn_scope = if uncallable {
-            fn_scope.with_uninstatiable_params(&params)
+            fn_scope.with_uninstatiable_params(&params, self)
         } else {
             fn_scope.with_params(&params, ll_function, self)
         }
-        .with_return_type(return_type.clone());
+        .with_return_type(
+            return_type.clone(),
+            function_info.signature.return_type_name.clone(),
+        );
+
+        if self.is_drop_fn(function.name, &params) {
+            fn_scope = fn_scope.without_param_drops();
+        }
 
-        let return_value = self.generate_codeblock(&function.body, &mut fn_scope)?;
+        let return_value = self.generate_codeblock(body, &mut fn_scope)?;
 
         if !return_value.type_.is(&return_type) {
             return Err(codegen::error::incorrect_implicit_return_type(
-                function.body.as_sref(),
+                body.as_sref(),
                 &return_type,
+                &function_info.signature.return_type_name,
                 &return_value.type_,
             ));
         }
@@ -131,7 +171,27 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
             self.builder.build_unreachable();
         }
 
-        Ok(())
+        self.check_moves(ll_function)
+    }
+
+    /// Checks if the function is the drop function of the struct that it takes
+    fn is_drop_fn(&self, fn_name: &str, params: &[(S<&str>, Type)]) -> bool {
+        let [(_, Type::Struct { path })] = params else {
+            return false;
+        };
+
+        let struct_info = self
+            .c
+            .name_store
+            .get_item_from_string(path)
+            .unwrap()
+            .as_struct()
+            .unwrap();
+
+        struct_info
+            .drop_fn
+            .as_ref()
+            .is_some_and(|drop_fn| *drop_fn == format!("{}::{fn_name}", self.crate_name))
     }
 
     /// Generates a codeblock: NOTE: this will NOT create a new scope. The caller should create one for this block
@@ -172,9 +232,13 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
         }
 
         let return_value: Option<RValue> = implicit_return
-            .map(|r| self.generate_rvalue(r, scope))
+            .map(|r| self.generate_owned_rvalue(r, scope))
             .transpose()?;
 
+        if terminating_idx.is_none() && return_value.as_ref().is_none_or(|r| r.val.is_some()) {
+            scope.generate_drops(self, ScopeExit::Fallthrough);
+        }
+
         if let Some(terminating_idx) = terminating_idx {
             if let Some(dead_code) = statements
                 .get(terminating_idx + 1..)
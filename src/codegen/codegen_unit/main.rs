@@ -1,10 +1,24 @@
-use wllvm::attribute::AttrKind;
+use std::ffi::CString;
+
+use wllvm::{
+    attribute::AttrKind,
+    builder::IntPredicate,
+    type_::AsmDialect,
+    value::{IntValue, PtrValue, StructValue},
+};
 
 use crate::{
     codegen::{error, types::Type, CodegenUnit},
     error_handling::Diagnostic,
 };
 
+/// The global that the command-line arguments are stored in as a `[str]`
+pub const ARGS_GLOBAL: &str = "__wlab_args";
+/// The global that the environment variables (eg `HOME=/root`) are stored in as a `[str]`
+pub const ENV_GLOBAL: &str = "__wlab_env";
+/// The function that is called by `_start`
+const LANG_START: &str = "__wlab_lang_start";
+
 impl<'ctx> CodegenUnit<'_, 'ctx> {
     pub fn generate_entrypoint(&self) -> Result<(), Diagnostic> {
         let main = self
@@ -15,23 +29,104 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
             .as_function()
             .unwrap();
 
-        let llvm_function = self.module.add_function(
-            c"_start",
-            self.c
-                .context
-                .fn_type(*self.c.context.void_type(), &[], false),
+        let old_block = self.builder.current_block();
+
+        let (args, env) = if self.c.params.hosted {
+            self.generate_c_main()
+        } else {
+            self.generate_lang_start()
+        };
+
+        for (name, value) in [(ARGS_GLOBAL, args), (ENV_GLOBAL, env)] {
+            let global = self
+                .module
+                .add_global(*self.c.core_types.slice, &CString::new(name).unwrap());
+            global.set_initializer(Some(*self.c.core_types.slice.const_null()));
+
+            self.builder.build_store(*value, global.as_ptr());
+        }
+
+        let main_args: &[wllvm::Value] = if main.signature.params.is_empty() {
+            &[]
+        } else {
+            &[*args]
+        };
+
+        let main_ret = self.builder.build_fn_call(main.function, main_args, c"");
+
+        // The program exits with the value returned from `main` if it returns an `i32`
+        let exit_code = if main.signature.return_type == Type::i(32) {
+            main_ret
+        } else {
+            *self.c.context.int_type(32).const_(0, false)
+        };
+
+        // In hosted mode, `main` returns to libc which exits the process
+        if self.c.params.hosted {
+            self.builder.build_ret(exit_code);
+        } else {
+            self.generate_exit(exit_code)?;
+        }
+
+        if let Some(old_block) = old_block {
+            self.builder.position_at_end(old_block)
+        }
+
+        Ok(())
+    }
+
+    /// Generates the C `main` function that libc calls in hosted mode and positions the builder
+    /// inside of it. The command-line arguments and environment variables are returned.
+    fn generate_c_main(&self) -> (StructValue<'ctx>, StructValue<'ctx>) {
+        let context = self.c.context;
+        let i32 = context.int_type(32);
+        let ptr_type = context.ptr_type();
+
+        let c_main = self.module.add_function(
+            c"main",
+            context.fn_type(*i32, &[*i32, *ptr_type, *ptr_type], false),
         );
 
-        llvm_function.add_attribute(self.c.context.attribute(AttrKind::NoReturn()));
-        llvm_function.add_attribute(self.c.context.attribute(AttrKind::NoUnwind()));
+        self.builder.position_at_end(c_main.add_basic_block(c""));
 
-        let old_block = self.builder.current_block();
+        let argc = IntValue::try_from(c_main.param(0).unwrap()).unwrap();
+        let argc = self.builder.build_sext(argc, self.c.core_types.isize, c"");
+        let argv = PtrValue::try_from(c_main.param(1).unwrap()).unwrap();
+        let envp = PtrValue::try_from(c_main.param(2).unwrap()).unwrap();
+
+        self.generate_process_arguments(argc, argv, envp)
+    }
 
-        let fn_block = llvm_function.add_basic_block(c"");
-        self.builder.position_at_end(fn_block);
+    /// Generates `_start` along with `lang_start` and positions the builder inside of
+    /// `lang_start`. The command-line arguments and environment variables are returned.
+    fn generate_lang_start(&self) -> (StructValue<'ctx>, StructValue<'ctx>) {
+        self.generate_start_stub();
+
+        // `lang_start` does the actual work of `_start` once the stack has been set up. It takes
+        // the initial stack pointer of the process
+        let lang_start = self.module.add_function(
+            &CString::new(LANG_START).unwrap(),
+            self.c.context.fn_type(
+                *self.c.context.void_type(),
+                &[*self.c.context.ptr_type()],
+                false,
+            ),
+        );
 
-        self.builder.build_fn_call(main.function, &[], c"");
+        lang_start.add_attribute(self.c.context.attribute(AttrKind::NoReturn()));
+        lang_start.add_attribute(self.c.context.attribute(AttrKind::NoUnwind()));
 
+        self.builder
+            .position_at_end(lang_start.add_basic_block(c""));
+
+        let stack = PtrValue::try_from(lang_start.param(0).unwrap()).unwrap();
+        let (argc, argv, envp) = self.read_initial_stack(stack);
+
+        self.generate_process_arguments(argc, argv, envp)
+    }
+
+    /// Exits the process with `exit_code` by calling `std::exit`
+    fn generate_exit(&self, exit_code: wllvm::Value<'ctx>) -> Result<(), Diagnostic> {
         let Some(exit_fn) = self
             .c
             .name_store
@@ -53,18 +148,190 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
             exit_fn
         });
 
-        self.builder.build_fn_call(
-            exit_fn,
-            &[*self.c.context.int_type(32).const_(0, false)],
-            c"",
+        self.builder.build_fn_call(exit_fn, &[exit_code], c"");
+
+        self.builder.build_unreachable();
+
+        Ok(())
+    }
+
+    /// Generates `_start`, the entrypoint of the program. The kernel enters it with a 16-byte
+    /// aligned stack but without a return address, so it is a naked function that sets up the
+    /// stack like a normal call before calling `lang_start`.
+    fn generate_start_stub(&self) {
+        let context = self.c.context;
+        let void_fn = context.fn_type(*context.void_type(), &[], false);
+
+        let start = self.module.add_function(c"_start", void_fn);
+
+        start.add_attribute(context.attribute(AttrKind::Naked()));
+        start.add_attribute(context.attribute(AttrKind::NoReturn()));
+        start.add_attribute(context.attribute(AttrKind::NoUnwind()));
+
+        // The initial stack pointer is passed to `lang_start`. The frame pointer is cleared to
+        // mark the outermost frame
+        let stub = void_fn.inline_asm(
+            &format!(
+                "xor %ebp, %ebp\n\
+                 mov %rsp, %rdi\n\
+                 and $$-16, %rsp\n\
+                 call {LANG_START}\n\
+                 ud2"
+            ),
+            "",
+            true,
+            false,
+            AsmDialect::ATT,
+            false,
         );
 
+        let old_block = self.builder.current_block();
+
+        self.builder.position_at_end(start.add_basic_block(c""));
+        self.builder.build_ptr_call(void_fn, stub, &[], c"");
         self.builder.build_unreachable();
 
         if let Some(old_block) = old_block {
-            self.builder.position_at_end(old_block)
+            self.builder.position_at_end(old_block);
         }
+    }
 
-        Ok(())
+    /// Reads `argc`, `argv` and `envp` from the initial stack of the process. The kernel places
+    /// `argc`, followed by the null-terminated `argv` and `envp` arrays, at the stack pointer that
+    /// `_start` is entered with.
+    fn read_initial_stack(
+        &self,
+        stack: PtrValue<'ctx>,
+    ) -> (IntValue<'ctx>, PtrValue<'ctx>, PtrValue<'ctx>) {
+        let isize = self.c.core_types.isize;
+
+        let argc = IntValue::try_from(self.builder.build_load(*isize, stack, c"")).unwrap();
+        let argv = self
+            .builder
+            .build_gep(*isize, stack, &[isize.const_(1, false)], c"");
+
+        let envp_offset = self.builder.build_add(argc, isize.const_(1, false), c"");
+        let envp = self
+            .builder
+            .build_gep(*self.c.context.ptr_type(), argv, &[envp_offset], c"");
+
+        (argc, argv, envp)
+    }
+
+    /// Converts `argv` and the null-terminated `envp` array into the command-line arguments and
+    /// environment variables
+    fn generate_process_arguments(
+        &self,
+        argc: IntValue<'ctx>,
+        argv: PtrValue<'ctx>,
+        envp: PtrValue<'ctx>,
+    ) -> (StructValue<'ctx>, StructValue<'ctx>) {
+        let isize = self.c.core_types.isize;
+        let ptr_type = self.c.context.ptr_type();
+
+        let env_count = self.generate_counting_loop(
+            |idx| {
+                let entry = self.builder.build_gep(*ptr_type, envp, &[idx], c"");
+                let entry =
+                    IntValue::try_from(self.builder.build_load(*isize, entry, c"")).unwrap();
+
+                self.builder
+                    .build_icmp(IntPredicate::EQ, entry, isize.const_(0, false), c"")
+            },
+            |_| {},
+        );
+
+        let args = self.generate_c_string_slice(argv, argc);
+        let env = self.generate_c_string_slice(envp, env_count);
+
+        (args, env)
+    }
+
+    /// Converts an array of `count` null-terminated strings into a `[str]`. The `str`s are stored
+    /// on the stack of the current function.
+    fn generate_c_string_slice(
+        &self,
+        strings: PtrValue<'ctx>,
+        count: IntValue<'ctx>,
+    ) -> StructValue<'ctx> {
+        let context = self.c.context;
+        let str_type = self.c.core_types.str;
+        let i8 = context.int_type(8);
+
+        let array = self.builder.build_array_alloca(*str_type, count, c"");
+
+        self.generate_counting_loop(
+            |idx| self.builder.build_icmp(IntPredicate::EQ, idx, count, c""),
+            |idx| {
+                let string = self
+                    .builder
+                    .build_gep(*context.ptr_type(), strings, &[idx], c"");
+                let string =
+                    PtrValue::try_from(self.builder.build_load(*context.ptr_type(), string, c""))
+                        .unwrap();
+
+                let len = self.generate_counting_loop(
+                    |byte_idx| {
+                        let byte = self.builder.build_gep(*i8, string, &[byte_idx], c"");
+                        let byte =
+                            IntValue::try_from(self.builder.build_load(*i8, byte, c"")).unwrap();
+
+                        self.builder
+                            .build_icmp(IntPredicate::EQ, byte, i8.const_(0, false), c"")
+                    },
+                    |_| {},
+                );
+
+                let str_val = str_type.const_null();
+                let str_val = self.builder.build_insert_value(str_val, *string, 0, c"");
+                let str_val = self.builder.build_insert_value(str_val, *len, 1, c"");
+
+                let element = self.builder.build_gep(*str_type, array, &[idx], c"");
+                self.builder.build_store(*str_val, element);
+            },
+        );
+
+        let slice = self.c.core_types.slice.const_null();
+        let slice = self.builder.build_insert_value(slice, *array, 0, c"");
+        self.builder.build_insert_value(slice, *count, 1, c"")
+    }
+
+    /// Generates a loop with a counter that starts at zero. At the start of every iteration,
+    /// `is_end` is generated and the loop is left if it returns true. Otherwise, `body` is
+    /// generated and the counter is incremented. The final value of the counter is returned.
+    fn generate_counting_loop(
+        &self,
+        is_end: impl FnOnce(IntValue<'ctx>) -> IntValue<'ctx>,
+        body: impl FnOnce(IntValue<'ctx>),
+    ) -> IntValue<'ctx> {
+        let context = self.c.context;
+        let isize = self.c.core_types.isize;
+
+        let entry_block = self.builder.current_block().unwrap();
+        let loop_block = context.insert_basic_block_after(entry_block, c"");
+        let body_block = context.insert_basic_block_after(loop_block, c"");
+        let exit_block = context.insert_basic_block_after(body_block, c"");
+
+        self.builder.build_br(loop_block);
+
+        self.builder.position_at_end(loop_block);
+        let idx = self.builder.build_phi(*isize, c"");
+        let idx_val = IntValue::try_from(*idx).unwrap();
+        let end = is_end(idx_val);
+        self.builder.build_cond_br(end, exit_block, body_block);
+
+        self.builder.position_at_end(body_block);
+        body(idx_val);
+        let next_idx = self.builder.build_add(idx_val, isize.const_(1, false), c"");
+        self.builder.build_br(loop_block);
+
+        idx.add_incoming(
+            &[*isize.const_(0, false), *next_idx],
+            &[entry_block, self.builder.current_block().unwrap()],
+        );
+
+        self.builder.position_at_end(exit_block);
+
+        idx_val
     }
 }
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+
+use wllvm::{value::FnValue, BasicBlock};
+use wutil::Span;
+
+use crate::{
+    codegen::{codegen_unit::CodegenUnit, error, scope::Scope, values::RValue},
+    error_handling::{Diagnostic, Spanned as S},
+    parser::ast::{self, Expression},
+};
+
+/// Something that happens to a variable that is relevant to move checking. Events are recorded
+/// in the basic block that is being generated when they happen.
+pub enum MoveEvent {
+    /// The variable is given a new value (ie it is declared or assigned to)
+    Init(usize),
+    /// The value of the variable is read
+    Use(usize, S<String>),
+    /// The value of the variable is moved out
+    Move(usize, S<String>),
+}
+
+impl<'ctx> CodegenUnit<'_, 'ctx> {
+    /// Creates an id for a new variable
+    pub(crate) fn declare_variable(&self) -> usize {
+        let id = self.variable_count.get();
+        self.variable_count.set(id + 1);
+
+        self.record_move_event(MoveEvent::Init(id));
+
+        id
+    }
+
+    pub(crate) fn record_move_event(&self, event: MoveEvent) {
+        let block = self.builder.current_block().unwrap();
+        self.move_events.borrow_mut().push((block, event));
+    }
+
+    /// Records a read of a variable if its value can be moved
+    pub(crate) fn record_use(&self, scope: &Scope<'_, 'ctx>, name: S<&str>) {
+        let Some(var) = scope.get_variable(*name) else {
+            return;
+        };
+
+        if !var.value.type_().is_copy(self.c) {
+            self.record_move_event(MoveEvent::Use(var.id, S(name.to_string(), name.1)));
+        }
+    }
+
+    /// Generates an expression whose value is taken by its user (eg a function argument). If the
+    /// value is not `#[copy]`, it is moved out of the expression
+    pub fn generate_owned_rvalue(
+        &self,
+        expression: S<&ast::Expression>,
+        scope: &mut Scope<'_, 'ctx>,
+    ) -> Result<RValue<'ctx>, Diagnostic> {
+        let value = self.generate_rvalue(expression, scope)?;
+        self.move_out(expression, &value, scope)?;
+
+        Ok(value)
+    }
+
+    /// Moves `value` out of the place that `expression` refers to. The drop flag of a moved
+    /// variable is cleared so that it isn't dropped again
+    pub(crate) fn move_out(
+        &self,
+        expression: S<&ast::Expression>,
+        value: &RValue<'ctx>,
+        scope: &Scope<'_, 'ctx>,
+    ) -> Result<(), Diagnostic> {
+        if value.val.is_none() || value.type_.is_copy(self.c) {
+            return Ok(());
+        }
+
+        match *expression {
+            Expression::Identifier(ident) => {
+                let Some(var) = scope.get_variable(ident) else {
+                    unreachable!()
+                };
+
+                self.record_move_event(MoveEvent::Move(var.id, S(ident.to_string(), expression.1)));
+
+                if let Some(drop_flag) = var.drop_flag {
+                    self.builder
+                        .build_store(*self.c.core_types.bool.const_(0, false), drop_flag);
+                }
+
+                Ok(())
+            }
+            Expression::FieldAccess(..) if place_root(*expression).is_some() => {
+                Err(error::move_out_of_field(expression.1, &value.type_))
+            }
+            Expression::Index(..) => Err(error::move_out_of_slice(expression.1, &value.type_)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Gives a variable a new value after it has been assigned to as a whole. The old value is
+    /// dropped if it hasn't been moved out
+    pub(crate) fn reinitialize_variable(&self, scope: &Scope<'_, 'ctx>, name: &str) {
+        let Some(var) = scope.get_variable(name) else {
+            unreachable!()
+        };
+
+        self.record_move_event(MoveEvent::Init(var.id));
+
+        if let Some(drop_flag) = var.drop_flag {
+            self.builder
+                .build_store(*self.c.core_types.bool.const_(1, false), drop_flag);
+        }
+    }
+
+    /// Drops the current value of a variable that is about to be assigned to as a whole
+    pub(crate) fn drop_overwritten_variable(&self, scope: &Scope<'_, 'ctx>, name: &str) {
+        let Some(var) = scope.get_variable(name) else {
+            unreachable!()
+        };
+
+        if let Some(drop_flag) = var.drop_flag {
+            self.generate_flagged_drop(&var.value, drop_flag);
+        }
+    }
+
+    /// Checks that no variable of `function` is used after its value has been moved out. This
+    /// must be called once the body of the function has been generated.
+    pub(crate) fn check_moves(&self, function: FnValue<'ctx>) -> Result<(), Diagnostic> {
+        let events = std::mem::take(&mut *self.move_events.borrow_mut());
+        let blocks = function.basic_blocks();
+
+        let index: HashMap<_, _> = blocks
+            .iter()
+            .enumerate()
+            .map(|(i, block)| (block.raw(), i))
+            .collect();
+
+        let mut block_events: Vec<Vec<&MoveEvent>> = blocks.iter().map(|_| Vec::new()).collect();
+        for (block, event) in &events {
+            block_events[index[&block.raw()]].push(event);
+        }
+
+        let successors: Vec<Vec<usize>> = blocks
+            .iter()
+            .map(|block| {
+                block
+                    .successors()
+                    .iter()
+                    .map(|s: &BasicBlock| index[&s.raw()])
+                    .collect()
+            })
+            .collect();
+
+        // The variables that may have been moved at the start of every block along with where
+        // they were moved
+        let mut moved_in: Vec<HashMap<usize, Span>> =
+            blocks.iter().map(|_| HashMap::new()).collect();
+        let mut worklist: Vec<usize> = (0..blocks.len()).collect();
+
+        while let Some(block) = worklist.pop() {
+            let mut moved = moved_in[block].clone();
+
+            for event in &block_events[block] {
+                match event {
+                    MoveEvent::Init(var) => {
+                        moved.remove(var);
+                    }
+                    MoveEvent::Move(var, name) => {
+                        moved.entry(*var).or_insert(name.1);
+                    }
+                    MoveEvent::Use(..) => {}
+                }
+            }
+
+            for &successor in &successors[block] {
+                let mut changed = false;
+
+                for (var, span) in &moved {
+                    if !moved_in[successor].contains_key(var) {
+                        moved_in[successor].insert(*var, *span);
+                        changed = true;
+                    }
+                }
+
+                if changed && !worklist.contains(&successor) {
+                    worklist.push(successor);
+                }
+            }
+        }
+
+        for (block, events) in block_events.iter().enumerate() {
+            let mut moved = moved_in[block].clone();
+
+            for event in events {
+                match event {
+                    MoveEvent::Init(var) => {
+                        moved.remove(var);
+                    }
+                    MoveEvent::Use(var, name) | MoveEvent::Move(var, name) => {
+                        if let Some(moved_at) = moved.get(var) {
+                            return Err(error::use_after_move(S(name.as_str(), name.1), *moved_at));
+                        }
+
+                        if let MoveEvent::Move(..) = event {
+                            moved.insert(*var, name.1);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether the value of `expression` is owned by a variable rather than being a temporary
+pub(super) fn is_place(expression: &Expression) -> bool {
+    match expression {
+        Expression::Identifier(_) | Expression::Index(..) => true,
+        Expression::FieldAccess(..) => place_root(expression).is_some(),
+        _ => false,
+    }
+}
+
+/// Gets the variable that a field access expression (eg `a.b.c`) reads from
+pub(super) fn place_root<'a>(expression: &'a Expression<'a>) -> Option<&'a str> {
+    match expression {
+        Expression::Identifier(ident) => Some(ident),
+        Expression::FieldAccess(lhs, _) => place_root(lhs),
+        _ => None,
+    }
+}
@@ -0,0 +1,236 @@
+use wutil::Span;
+
+use crate::{
+    codegen::{error, namestore::NameStoreEntry, types::Type, CodegenContext},
+    error_handling::{Diagnostic, Spanned as S},
+    lexer::Number,
+    parser::ast::{Expression, Literal},
+    util::MaybeVec,
+};
+
+/// A value that is known at compile time. These are independent of any LLVM module and are
+/// converted into LLVM constants where they are used.
+#[derive(Clone, Debug)]
+pub enum ConstValue {
+    Int {
+        bits: u32,
+        words: Vec<u64>,
+    },
+    Bool(bool),
+    Char(char),
+    Str(String),
+    Bytes(Vec<u8>),
+    /// A struct value with its fields in declaration order
+    Struct {
+        path: String,
+        fields: Vec<ConstValue>,
+    },
+}
+
+/// An unevaluated constant expression, eg the default value of a struct field.
+///
+/// Struct initializers can only be evaluated once the fields of the struct are known, so they
+/// are stored in this form until then.
+pub enum ConstExpr {
+    Value(ConstValue, Type),
+    StructInitializer {
+        name: S<Vec<S<String>>>,
+        fields: Vec<(S<String>, S<ConstExpr>)>,
+    },
+}
+
+impl ConstExpr {
+    pub fn new(expr: &S<Expression>) -> Result<S<Self>, Diagnostic> {
+        let value = match &**expr {
+            Expression::Identifier("true") => (ConstValue::Bool(true), Type::bool),
+            Expression::Identifier("false") => (ConstValue::Bool(false), Type::bool),
+            Expression::Literal(Literal::Number(number)) => {
+                let (bits, words) = number_literal(number, expr.1)?;
+                (ConstValue::Int { bits, words }, Type::i(bits))
+            }
+            Expression::Literal(Literal::String(str)) => (ConstValue::Str(str.clone()), Type::str),
+            Expression::Literal(Literal::Char(char)) => (ConstValue::Char(*char), Type::char),
+            Expression::Literal(Literal::ByteString(bytes)) => {
+                (ConstValue::Bytes(bytes.clone()), Type::bytes())
+            }
+            Expression::StructInitializer {
+                name,
+                fields,
+                base: None,
+            } => {
+                let fields = fields
+                    .iter()
+                    .map(|field| {
+                        Ok((
+                            S(field.name.to_string(), field.name.1),
+                            Self::new(&field.val)?,
+                        ))
+                    })
+                    .collect::<Result<_, Diagnostic>>()?;
+
+                let name = name.iter().map(|s| S(s.to_string(), s.1)).collect();
+
+                return Ok(S(
+                    Self::StructInitializer {
+                        name: S(name, expr.1),
+                        fields,
+                    },
+                    expr.1,
+                ));
+            }
+            _ => return Err(error::non_constant_expression(expr.1)),
+        };
+
+        Ok(S(Self::Value(value.0, value.1), expr.1))
+    }
+}
+
+impl CodegenContext<'_> {
+    /// Evaluates a constant expression from `crate_name`. Any struct that is initialized must
+    /// already have its field defaults evaluated.
+    pub(super) fn evaluate_const_expr(
+        &self,
+        crate_name: &str,
+        expr: &S<ConstExpr>,
+        expected_type: &Type,
+    ) -> Result<ConstValue, Diagnostic> {
+        let (name, fields) = match &**expr {
+            ConstExpr::Value(value, type_) => {
+                if !type_.is(expected_type) {
+                    return Err(error::unexpected_type(expr.1, expected_type, type_));
+                }
+
+                return Ok(value.clone());
+            }
+            ConstExpr::StructInitializer { name, fields } => (name, fields),
+        };
+
+        let mut path = MaybeVec::new();
+        for segment in name.iter() {
+            path.push(S(segment.as_str(), segment.1));
+        }
+
+        let type_ = Type::new(self, crate_name, &S(path, name.1))?;
+
+        let Type::Struct { path } = &type_ else {
+            return Err(error::non_struct_type_initializer(S(&type_, name.1)));
+        };
+
+        if !type_.is(expected_type) {
+            return Err(error::unexpected_type(expr.1, expected_type, &type_));
+        }
+
+        let Some(NameStoreEntry::Struct(struct_info)) = self.name_store.get_item_from_string(path)
+        else {
+            unreachable!()
+        };
+
+        for (i, (field_name, _)) in fields.iter().enumerate() {
+            if let Some((first, _)) = fields[..i].iter().find(|(f, _)| **f == **field_name) {
+                return Err(error::duplicate_field(
+                    S(first.as_str(), first.1),
+                    field_name.1,
+                ));
+            }
+
+            if !struct_info.fields.iter().any(|f| f.name == **field_name) {
+                return Err(error::invalid_field(
+                    path,
+                    S(field_name.as_str(), field_name.1),
+                ));
+            }
+        }
+
+        let mut values = Vec::with_capacity(struct_info.fields.len());
+
+        for field in &struct_info.fields {
+            let Some((field_name, field_expr)) = fields.iter().find(|(f, _)| **f == field.name)
+            else {
+                let Some(default) = &field.default else {
+                    return Err(error::missing_field(&field.name, S(path.as_str(), name.1)));
+                };
+
+                values.push(default.clone());
+                continue;
+            };
+
+            field.check_visibility(path, crate_name, S(field_name.as_str(), field_name.1))?;
+
+            values.push(self.evaluate_const_expr(crate_name, field_expr, &field.ty)?);
+        }
+
+        Ok(ConstValue::Struct {
+            path: path.clone(),
+            fields: values,
+        })
+    }
+}
+
+/// Gets the width and value (as little-endian words) of an integer literal
+pub fn number_literal(number: &Number, span: Span) -> Result<(u32, Vec<u64>), Diagnostic> {
+    let bits = match number.suffix {
+        None => 32,
+        Some(suffix) => {
+            let suffix_span = Span::at(span.end - suffix.len()).with_end(span.end);
+
+            match suffix.strip_prefix("i").and_then(|n| n.parse::<u32>().ok()) {
+                Some(bits) if bits > 0 => bits,
+                _ if suffix.starts_with('f') => {
+                    return Err(error::float_literal(span));
+                }
+                _ => return Err(error::invalid_number_suffix(S(suffix, suffix_span))),
+            }
+        }
+    };
+
+    if number.is_float {
+        return Err(error::float_literal(span));
+    }
+
+    let words = parse_integer(number.digits, number.radix);
+
+    // Decimal literals must fit into a positive signed integer, while other literals may also
+    // set the sign bit (eg `0xFFi8`)
+    let available_bits = if number.radix == 10 { bits - 1 } else { bits };
+
+    if significant_bits(&words) > available_bits {
+        return Err(error::number_overflow(
+            S(&number.to_string(), span),
+            &Type::i(bits),
+            number.radix == 10,
+        ));
+    }
+
+    Ok((bits, words))
+}
+
+/// Converts the digits of an integer literal into little-endian 64-bit words. `_` separators are
+/// ignored.
+fn parse_integer(digits: &str, radix: u32) -> Vec<u64> {
+    let mut words = vec![0];
+
+    for digit in digits.chars().filter_map(|c| c.to_digit(radix)) {
+        let mut carry = u128::from(digit);
+
+        for word in &mut words {
+            let val = u128::from(*word) * u128::from(radix) + carry;
+
+            *word = val as u64;
+            carry = val >> 64;
+        }
+
+        if carry != 0 {
+            words.push(carry as u64);
+        }
+    }
+
+    words
+}
+
+/// Gets the number of bits required to represent an unsigned integer stored as little-endian words
+fn significant_bits(words: &[u64]) -> u32 {
+    words
+        .iter()
+        .rposition(|&w| w != 0)
+        .map_or(0, |i| i as u32 * 64 + (64 - words[i].leading_zeros()))
+}
@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use crate::{
     codegen::types::Type,
     diagnostic as d,
@@ -43,12 +45,34 @@ pub fn undefined_operator(operator: OpCode, span: Span, type_: &Type) -> Diagnos
         [Hint::new_error(format!("Value here is of type `{type_}`"), span)],
     }
 }
-pub fn unexpected_type(span: Span, expected: &Type, got: &Type) -> Diagnostic {
+/// An operator was used on a struct. Structs will be able to define operators by implementing
+/// `trait_`, but traits are not supported yet
+pub fn undefined_struct_operator(
+    operator: OpCode,
+    span: Span,
+    type_: &Type,
+    trait_: &str,
+) -> Diagnostic {
+    d! {
+        format!("Operator `{operator}` is not defined for type `{type_}`"),
+        [Hint::new_error(format!("`{type_}` does not implement `{trait_}`"), span)],
+    }
+}
+pub fn unexpected_type(span: Span, expected: &dyn Display, got: &Type) -> Diagnostic {
     d! {
         format!("Unexpected type: expected `{expected}`; got `{got}`"),
         [Hint::new_error(format!("value here of type `{got}`"), span)]
     }
 }
+pub fn invalid_cast(span: Span, from: &Type, to: S<&Type>) -> Diagnostic {
+    d! {
+        format!("Cannot convert type `{from}` into `{}`", *to),
+        [
+            Hint::new_error(format!("value here is of type `{from}`"), span),
+            Hint::new_info(format!("converted into `{}` here", *to), to.1),
+        ]
+    }
+}
 pub fn unexpected_break_type(expected: S<&Type>, got: S<&Type>) -> Diagnostic {
     d! {
         format!("Unexpected type: expected `{}`; got `{}`", expected.0, got.0),
@@ -64,13 +88,41 @@ pub fn invalid_param_count(span: Span, expected: usize, got: usize) -> Diagnosti
         [Hint::new_error("Function called here", span)]
     }
 }
-pub fn invalid_number(num: S<&str>) -> Diagnostic {
+pub fn invalid_number_suffix(suffix: S<&str>) -> Diagnostic {
     d! {
-        format!("Invalid numberical literal `{}`", &*num),
-        [Hint::new_error("Literal used here", num.1)]
+        format!("Invalid number suffix `{}`", *suffix),
+        [Hint::new_error("expected an integer type such as `i64`", suffix.1)]
     }
 }
-pub fn incorrect_explicit_return_type(expected: &Type, got: S<&Type>) -> Diagnostic {
+pub fn float_literal(span: Span) -> Diagnostic {
+    d! {
+        "Floating-point numbers are not supported yet",
+        [Hint::new_error("", span)]
+    }
+}
+pub fn number_overflow(num: S<&str>, type_: &Type, is_decimal: bool) -> Diagnostic {
+    let Type::i(bits) = *type_ else {
+        unreachable!()
+    };
+
+    let hint = if !is_decimal {
+        format!("`{type_}` only has {bits} bits")
+    } else if bits <= 128 {
+        format!(
+            "the maximum value of `{type_}` is `{}`",
+            // `i1` has no room for any positive value
+            u128::MAX.checked_shr(129 - bits).unwrap_or(0)
+        )
+    } else {
+        format!("the maximum value of `{type_}` is `2^{} - 1`", bits - 1)
+    };
+
+    d! {
+        format!("Literal `{}` does not fit into type `{type_}`", *num),
+        [Hint::new_error(hint, num.1)]
+    }
+}
+pub fn incorrect_explicit_return_type(expected: &str, got: S<&Type>) -> Diagnostic {
     d! {
         format!("Incorrect return type: expected `{expected}`; got `{}`", *got),
         [Hint::new_error("", got.1)]
@@ -79,11 +131,12 @@ pub fn incorrect_explicit_return_type(expected: &Type, got: S<&Type>) -> Diagnos
 pub fn incorrect_implicit_return_type(
     body: S<&CodeBlock>,
     expected: &Type,
+    expected_name: &str,
     got: &Type,
 ) -> Diagnostic {
     if body.body.is_empty() {
         return d! {
-            format!("Expected return type `{expected}` from function body"),
+            format!("Expected return type `{expected_name}` from function body"),
             [Hint::new_error("Function body is empty", body.1)]
         };
     }
@@ -97,13 +150,13 @@ pub fn incorrect_implicit_return_type(
 
     if let Some(semicolon) = body.trailing_semicolon {
         return d! {
-            format!("Incorrect return type; expected `{expected}`, got `()`"),
+            format!("Incorrect return type; expected `{expected_name}`, got `()`"),
             [Hint::new_error("`()` explicitly returned because of this semicolon here", semicolon)]
         };
     }
 
     d! {
-        format!("Incorrect return type; expected `{expected}`, got `{got}`"),
+        format!("Incorrect return type; expected `{expected_name}`, got `{got}`"),
         [Hint::new_error(format!("Expression here is of type `{got}`"), body.body.last().unwrap().1)]
     }
 }
@@ -126,6 +179,22 @@ pub fn non_function_attribute(attr: &S<Attribute>) -> Diagnostic {
     }
 }
 
+pub fn c_abi_str(span: Span) -> Diagnostic {
+    d! {
+        "`str` cannot be passed to or returned from functions that use the C calling convention",
+        [ Hint::new_error("C strings need pointer types, which aren't supported yet", span) ]
+    }
+}
+
+pub fn extern_function_attribute(attr: &S<Attribute>) -> Diagnostic {
+    d! {
+        "Attributes cannot be used on `extern` functions",
+        [
+            Hint::new_error("`extern` functions are only declarations", attr.1),
+        ]
+    }
+}
+
 pub fn non_struct_attribute(attr: &S<Attribute>) -> Diagnostic {
     d! {
         "Invalid struct attribute",
@@ -135,6 +204,34 @@ pub fn non_struct_attribute(attr: &S<Attribute>) -> Diagnostic {
     }
 }
 
+pub fn invalid_alignment(align: S<u32>) -> Diagnostic {
+    d! {
+        format!("Invalid alignment `{}`; alignment must be a power of two", *align),
+        [
+            Hint::new_error("", align.1)
+        ]
+    }
+}
+
+pub fn align_outside_struct(span: Span) -> Diagnostic {
+    d! {
+        "`#[align]` can only be used on structs",
+        [
+            Hint::new_error("aligned globals are not supported because there are no globals yet", span)
+        ]
+    }
+}
+
+pub fn packed_and_aligned(packed: Span, align: S<u32>) -> Diagnostic {
+    d! {
+        format!("A packed struct cannot have an alignment of {}", *align),
+        [
+            Hint::new_info("packed structs have an alignment of 1", packed),
+            Hint::new_error("", align.1),
+        ]
+    }
+}
+
 pub fn non_module_attribute(attr: &S<Attribute>) -> Diagnostic {
     d! {
         "Invalid module attribute",
@@ -154,6 +251,46 @@ pub fn multiple_intrinsic_attributes(first_intrinsic: Span, second_intrinsic: Sp
     }
 }
 
+pub fn invalid_global_allocator(kind: S<&str>) -> Diagnostic {
+    d! {
+        format!("Invalid global allocator function `{}`; expected `alloc` or `dealloc`", *kind),
+        [
+            Hint::new_error("", kind.1),
+        ]
+    }
+}
+
+pub fn duplicate_global_allocator(
+    kind: S<&str>,
+    other_crate: &str,
+    this_crate: &str,
+) -> Diagnostic {
+    d! {
+        format!("Global allocator `{}` function defined twice: first defined in `{other_crate}` then in `{this_crate}`", *kind),
+        [
+            Hint::new_error("", kind.1),
+        ]
+    }
+}
+
+pub fn partial_global_allocator(kind: S<&str>, missing: &str) -> Diagnostic {
+    d! {
+        format!("Global allocator `{}` function replaced without replacing `{missing}`", *kind),
+        [
+            Hint::new_error("`alloc` and `dealloc` have to be replaced together", kind.1),
+        ]
+    }
+}
+
+pub fn global_allocator_signature(params_span: Span, expected: &str) -> Diagnostic {
+    d! {
+        format!("Invalid global allocator signature; expected `{expected}`"),
+        [
+            Hint::new_error("", params_span),
+        ]
+    }
+}
+
 pub fn missing_crate_name() -> Diagnostic {
     d! {
         "No crate name declared",
@@ -208,6 +345,66 @@ pub fn not_type(name: S<&str>) -> Diagnostic {
     }
 }
 
+pub fn recursive_type_alias(alias: S<&str>, cycle: &[String]) -> Diagnostic {
+    let mut chain = String::new();
+    for path in cycle {
+        chain.push_str(&format!("`{path}` -> "));
+    }
+
+    d! {
+        format!("Type alias `{}` is defined in terms of itself: {chain}`{}`", *alias, *alias),
+        [
+            Hint::new_error("cycle occurs here", alias.1)
+        ]
+    }
+}
+
+/// `cycle` contains the paths of the structs in the cycle, and `fields` contains the fields that
+/// form the cycle
+/// `fields` are the fields of the cycle that are in the file the error is reported in, and
+/// `other_fields` are the paths of the structs and the names of the fields that are not
+pub fn recursive_struct(
+    cycle: &[&str],
+    fields: &[(Span, &Type)],
+    other_fields: &[(&str, &str)],
+) -> Diagnostic {
+    let mut chain = String::new();
+    for path in cycle {
+        chain.push_str(&format!("`{path}` -> "));
+    }
+
+    let mut msg = format!(
+        "Struct `{}` contains itself: {chain}`{}`",
+        cycle[0], cycle[0]
+    );
+
+    if !other_fields.is_empty() {
+        let names: Vec<String> = other_fields
+            .iter()
+            .map(|(path, field)| format!("`{path}.{field}`"))
+            .collect();
+
+        msg.push_str(&format!(" (through {} in other files)", names.join(", ")));
+    }
+
+    let mut diagnostic = d! { msg, [] };
+
+    for (i, &(span, type_)) in fields.iter().enumerate() {
+        // Slices are stored behind a pointer, so they don't make the struct infinitely large
+        let hint = if i == 0 {
+            format!(
+                "field of type `{type_}`; insert indirection (eg `[{type_}]`) to break the cycle"
+            )
+        } else {
+            format!("field of type `{type_}`")
+        };
+
+        diagnostic.hints.push(Hint::new_error(hint, span));
+    }
+
+    diagnostic
+}
+
 pub fn not_function_path(path: &S<Path>) -> Diagnostic {
     let name: String = util::Intersperse::new(path.iter().map(|n| **n), "::").collect();
 
@@ -268,6 +465,24 @@ pub fn private_function(crate_name: S<&str>, fn_name: S<&str>) -> Diagnostic {
     }
 }
 
+pub fn private_type(crate_name: S<&str>, type_name: S<&str>) -> Diagnostic {
+    d! {
+        format!("Cannot access private type `{}::{}`", *crate_name, *type_name),
+        [
+            Hint::new_error("", error_handling::span_of(&[crate_name, type_name]).unwrap())
+        ]
+    }
+}
+
+pub fn private_field(struct_path: &str, field: S<&str>) -> Diagnostic {
+    d! {
+        format!("Cannot access private field `{}` of struct `{struct_path}`", *field),
+        [
+            Hint::new_error("", field.1)
+        ]
+    }
+}
+
 pub fn non_struct_element_access(span: Span, type_: &Type, field: &str) -> Diagnostic {
     d! {
         format!("Cannot access field `{field}` of non-struct type `{type_}`"),
@@ -275,6 +490,20 @@ pub fn non_struct_element_access(span: Span, type_: &Type, field: &str) -> Diagn
     }
 }
 
+pub fn non_slice_index(type_: S<&Type>) -> Diagnostic {
+    d! {
+        format!("Cannot index into a value of type `{}`", *type_),
+        [ Hint::new_error("only slices can be indexed", type_.1) ]
+    }
+}
+
+pub fn non_integer_index(type_: S<&Type>) -> Diagnostic {
+    d! {
+        "Slice index must be an integer",
+        [ Hint::new_error(format!("index is of type `{}`", *type_), type_.1) ]
+    }
+}
+
 pub fn non_struct_type_initializer(type_: S<&Type>) -> Diagnostic {
     d! {
         format!("Cannot create struct of type `{0}` because `{0}` is not a struct", *type_),
@@ -308,6 +537,58 @@ pub fn missing_field(field: &str, struct_name: S<&str>) -> Diagnostic {
     }
 }
 
+pub fn invalid_drop_function(function: S<&str>, struct_path: &str) -> Diagnostic {
+    d! {
+        format!("Drop function `{}` must have the signature `fn({struct_path})`", *function),
+        [ Hint::new_error("drop function set here", function.1) ]
+    }
+}
+
+pub fn copy_and_drop(struct_path: &str, drop_attr: Span) -> Diagnostic {
+    d! {
+        format!("Struct `{struct_path}` cannot be `#[copy]` because it has a drop function"),
+        [ Hint::new_error("drop function set here", drop_attr) ]
+    }
+}
+
+pub fn non_copy_field(struct_path: &str, field_type: S<&Type>) -> Diagnostic {
+    d! {
+        format!("Struct `{struct_path}` cannot be `#[copy]` because it contains a non-copy field"),
+        [ Hint::new_error(format!("field of type `{}`", *field_type), field_type.1) ]
+    }
+}
+
+pub fn use_after_move(name: S<&str>, moved_at: Span) -> Diagnostic {
+    d! {
+        format!("Use of moved value `{}`", *name),
+        [
+            Hint::new_error("value used here after move", name.1),
+            Hint::new_info("value moved here", moved_at),
+        ]
+    }
+}
+
+pub fn move_out_of_field(span: Span, type_: &Type) -> Diagnostic {
+    d! {
+        format!("Cannot move a value of type `{type_}` out of a field of a variable"),
+        [ Hint::new_error(format!("`{type_}` is not `#[copy]`"), span) ]
+    }
+}
+
+pub fn move_out_of_slice(span: Span, type_: &Type) -> Diagnostic {
+    d! {
+        format!("Cannot move a value of type `{type_}` out of a slice"),
+        [ Hint::new_error(format!("`{type_}` is not `#[copy]`"), span) ]
+    }
+}
+
+pub fn non_constant_expression(span: Span) -> Diagnostic {
+    d! {
+        "Expected a constant expression",
+        [ Hint::new_error("only literals and struct initializers are allowed here", span) ]
+    }
+}
+
 pub fn duplicate_main(other_main: &str, this_crate: &str, span: Span) -> Diagnostic {
     d! {
         format!("`main` function defined twice: first defined in `{other_main}` then in `{this_crate}`"),
@@ -317,8 +598,8 @@ pub fn duplicate_main(other_main: &str, this_crate: &str, span: Span) -> Diagnos
 
 pub fn main_arguments(span: Span) -> Diagnostic {
     d! {
-        "main function cannot have arguments",
-        [ Hint::new_error("", span) ]
+        "main function can only take the command-line arguments",
+        [ Hint::new_error("expected `(args: [str])` or `()`", span) ]
     }
 }
 
@@ -338,7 +619,7 @@ pub fn exit_arguments() -> Diagnostic {
 
 pub fn main_return_type(span: Span) -> Diagnostic {
     d! {
-        "main function must return `()` (unit) type",
+        "main function must return `()` (unit) or `i32`",
         [ Hint::new_error("", span) ]
     }
 }
@@ -356,3 +637,44 @@ pub fn return_outside_of_function(span: Span) -> Diagnostic {
         [ Hint::new_error("", span) ]
     }
 }
+
+pub fn local_type_alias(span: Span) -> Diagnostic {
+    d! {
+        "Type aliases can only be declared at module level",
+        [ Hint::new_error("", span) ]
+    }
+}
+
+pub fn invalid_asm_register(register: S<&str>) -> Diagnostic {
+    d! {
+        format!("Invalid register `{}`", *register),
+        [Hint::new_error("", register.1)],
+    }
+}
+
+pub fn duplicate_asm_register(register: S<&str>, previous: Span) -> Diagnostic {
+    d! {
+        format!("Register `{}` is used by more than one input or output", *register),
+        [
+            Hint::new_error("", register.1),
+            Hint::new_info("previously used here", previous),
+        ],
+    }
+}
+
+pub fn clobbered_asm_output(register: S<&str>, output: Span) -> Diagnostic {
+    d! {
+        format!("Register `{}` is clobbered but is also an output", *register),
+        [
+            Hint::new_error("", register.1),
+            Hint::new_info("used as an output here", output),
+        ],
+    }
+}
+
+pub fn invalid_asm_operand_type(span: Span, type_: &Type) -> Diagnostic {
+    d! {
+        format!("Values of type `{type_}` cannot be passed in registers; only integers of up to 64 bits and `char` can"),
+        [Hint::new_error("", span)],
+    }
+}
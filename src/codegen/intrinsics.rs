@@ -1,11 +1,20 @@
+use std::ffi::CString;
+
 use wllvm::{
-    type_::AsmDialect,
-    value::{IntValue, StructValue},
+    builder::IntPredicate,
+    type_::{AsmDialect, FnType},
+    value::{FnValue, GlobalVariable, IntValue, Linkage, PtrValue, StructValue},
 };
 use wutil::Span;
 
 use crate::{
-    codegen::{self, namestore::FunctionInfo, types::Type, CodegenUnit},
+    codegen::{
+        self,
+        codegen_unit::main::{ARGS_GLOBAL, ENV_GLOBAL},
+        namestore::FunctionInfo,
+        types::Type,
+        CodegenUnit,
+    },
     error_handling::{Diagnostic, Spanned as S},
     parser::ast,
 };
@@ -14,26 +23,39 @@ impl<'ctx> CodegenUnit<'_, 'ctx> {
     pub fn add_intrinsic(
         &self,
         function: &S<ast::Function>,
-        function_info: &FunctionInfo,
+        function_info: &FunctionInfo<'ctx>,
         params: &[(S<&str>, Type)],
         intrinsic: S<&str>,
     ) -> Result<(), Diagnostic> {
-        if !function.body.body.is_empty() {
-            return Err(codegen::error::non_empty_intrinsic(function.body.1));
+        if let Some(body) = function.body.as_ref().filter(|body| !body.body.is_empty()) {
+            return Err(codegen::error::non_empty_intrinsic(body.1));
         }
 
         match *intrinsic {
             "write" => add_write(function.params.1, self, function_info, params),
             "exit" => add_exit(function.params.1, self, function_info, params),
-            _ => Err(codegen::error::invalid_intrinsic(intrinsic)),
+            "args" => add_args(function.params.1, self, function_info, params),
+            "env_var" => add_env_var(function.params.1, self, function_info, params),
+            "static_word" => add_static_word(function.params.1, self, function_info, params),
+            _ => {
+                if let Some(num_args) = intrinsic
+                    .strip_prefix("syscall")
+                    .and_then(|n| n.parse().ok())
+                    .filter(|&n| n <= SYSCALL_REGISTERS.len())
+                {
+                    return add_syscall(function.params.1, self, function_info, params, num_args);
+                }
+
+                Err(codegen::error::invalid_intrinsic(intrinsic))
+            }
         }
     }
 }
 
-fn add_write(
+fn add_write<'ctx>(
     params_span: Span,
-    unit: &CodegenUnit<'_, '_>,
-    function_info: &FunctionInfo,
+    unit: &CodegenUnit<'_, 'ctx>,
+    function_info: &FunctionInfo<'ctx>,
     params: &[(S<&str>, Type)],
 ) -> Result<(), Diagnostic> {
     if !matches!(params, [(_, Type::i(32)), (_, Type::str)]) {
@@ -57,33 +79,9 @@ fn add_write(
     let main_block = function.add_basic_block(c"");
     unit.builder.position_at_end(main_block);
 
-    let syscall_type = unit.c.context.fn_type(
-        *i64,
-        &[
-            *i64,
-            *i64,
-            *unit.c.context.ptr_type(),
-            *unit.c.core_types.isize,
-        ],
-        false,
-    );
-
-    let syscall = syscall_type.inline_asm(
-        "syscall",
-        "=r,{rax},{rdi},{rsi},{rdx}",
-        true,
-        false,
-        AsmDialect::ATT,
-        false,
-    );
-
     // params //
 
-    let fd = unit.builder.build_zext(
-        IntValue::try_from(function.param(0).unwrap()).unwrap(),
-        i64,
-        c"",
-    );
+    let fd = IntValue::try_from(function.param(0).unwrap()).unwrap();
 
     let data_ptr = unit
         .builder
@@ -105,12 +103,28 @@ fn add_write(
 
     // do call //
 
-    unit.builder.build_ptr_call(
-        syscall_type,
-        syscall,
-        &[*i64.const_(1, false), *fd, data_ptr, str_len],
-        c"",
-    );
+    if unit.c.params.hosted {
+        let write = libc_function(
+            unit,
+            "write",
+            unit.c.context.fn_type(
+                *unit.c.core_types.isize,
+                &[
+                    *unit.c.context.int_type(32),
+                    *unit.c.context.ptr_type(),
+                    *unit.c.core_types.isize,
+                ],
+                false,
+            ),
+        );
+
+        unit.builder
+            .build_fn_call(write, &[*fd, data_ptr, str_len], c"");
+    } else {
+        let fd = unit.builder.build_zext(fd, i64, c"");
+
+        build_syscall(unit, i64.const_(1, false), &[*fd, data_ptr, str_len]);
+    }
 
     let zero = unit.c.core_types.unit.const_(&[]);
     unit.builder.build_ret(*zero);
@@ -118,10 +132,10 @@ fn add_write(
     Ok(())
 }
 
-fn add_exit(
+fn add_exit<'ctx>(
     params_span: Span,
-    unit: &CodegenUnit<'_, '_>,
-    function_info: &FunctionInfo,
+    unit: &CodegenUnit<'_, 'ctx>,
+    function_info: &FunctionInfo<'ctx>,
     params: &[(S<&str>, Type)],
 ) -> Result<(), Diagnostic> {
     if !matches!(params, [(_, Type::i(32))]) {
@@ -145,36 +159,339 @@ fn add_exit(
     let main_block = function.add_basic_block(c"");
     unit.builder.position_at_end(main_block);
 
-    let syscall_type = unit.c.context.fn_type(*i64, &[*i64, *i64], false);
+    // params //
 
-    let syscall = syscall_type.inline_asm(
-        "syscall",
-        "=r,{rax},{rdi}",
-        true,
-        false,
-        AsmDialect::ATT,
-        false,
-    );
+    let exit_code = IntValue::try_from(function.param(0).unwrap()).unwrap();
 
-    // params //
+    // do call //
+
+    if unit.c.params.hosted {
+        // Unlike the `exit` system call, this flushes libc's buffers and runs `atexit` handlers
+        let exit = libc_function(
+            unit,
+            "exit",
+            unit.c.context.fn_type(
+                *unit.c.context.void_type(),
+                &[*unit.c.context.int_type(32)],
+                false,
+            ),
+        );
+
+        unit.builder.build_fn_call(exit, &[*exit_code], c"");
+    } else {
+        let exit_code = unit.builder.build_zext(exit_code, i64, c"");
+
+        build_syscall(unit, i64.const_(60, false), &[*exit_code]);
+    }
+
+    let zero = unit.c.core_types.unit.const_(&[]);
+    unit.builder.build_ret(*zero);
+
+    Ok(())
+}
+
+/// The registers that the arguments of a system call are passed in
+const SYSCALL_REGISTERS: [&str; 6] = ["rdi", "rsi", "rdx", "r10", "r8", "r9"];
+
+/// Generates a Linux system call. The result is returned in `rax`, and `rcx` and `r11` are
+/// clobbered by the `syscall` instruction.
+fn build_syscall<'ctx>(
+    unit: &CodegenUnit<'_, 'ctx>,
+    number: IntValue<'ctx>,
+    args: &[wllvm::Value<'ctx>],
+) -> wllvm::Value<'ctx> {
+    let i64 = unit.c.context.int_type(64);
+
+    let mut param_types = vec![*i64];
+    param_types.extend(args.iter().map(|arg| arg.type_()));
+
+    let syscall_type = unit.c.context.fn_type(*i64, &param_types, false);
+
+    let mut constraints = String::from("={rax},{rax}");
+    for register in &SYSCALL_REGISTERS[..args.len()] {
+        constraints += &format!(",{{{register}}}");
+    }
+    constraints += ",~{rcx},~{r11},~{memory}";
+
+    let syscall =
+        syscall_type.inline_asm("syscall", &constraints, true, false, AsmDialect::ATT, false);
+
+    let mut call_args = vec![*number];
+    call_args.extend_from_slice(args);
+
+    unit.builder
+        .build_ptr_call(syscall_type, syscall, &call_args, c"")
+}
+
+/// Generates `syscallN`, which takes the system call number followed by `N` arguments and returns
+/// the raw result of the system call. Integers are extended to 64 bits, and `str`s and slices
+/// are passed as a pointer to their data.
+fn add_syscall<'ctx>(
+    params_span: Span,
+    unit: &CodegenUnit<'_, 'ctx>,
+    function_info: &FunctionInfo<'ctx>,
+    params: &[(S<&str>, Type)],
+    num_args: usize,
+) -> Result<(), Diagnostic> {
+    let is_valid_arg = |type_: &Type| match type_ {
+        Type::i(n) => *n <= 64,
+        Type::bool | Type::char | Type::str | Type::Slice(_) => true,
+        Type::unit | Type::never | Type::Struct { .. } => false,
+    };
+
+    let is_valid = matches!(params.first(), Some((_, Type::i(64))))
+        && params.len() == num_args + 1
+        && params[1..].iter().all(|(_, type_)| is_valid_arg(type_));
+
+    if !is_valid {
+        let mut expected = String::from("(i64");
+        for _ in 0..num_args {
+            expected += ", <integer, str, or slice>";
+        }
+        expected += ")";
+
+        return Err(codegen::error::invalid_intrinsic_params(
+            params_span,
+            &expected,
+        ));
+    }
+
+    if function_info.signature.return_type != Type::i(64) {
+        return Err(codegen::error::invalid_intrinsic_ret_type(
+            params_span,
+            &Type::i(64),
+        ));
+    }
+
+    let builder = &unit.builder;
+    let i64 = unit.c.context.int_type(64);
+
+    let function = function_info.function;
+
+    let main_block = function.add_basic_block(c"");
+    builder.position_at_end(main_block);
+
+    let number = IntValue::try_from(function.param(0).unwrap()).unwrap();
+
+    let args: Vec<wllvm::Value> = params[1..]
+        .iter()
+        .enumerate()
+        .map(|(i, (_, type_))| {
+            let arg = function.param(i as u32 + 1).unwrap();
+
+            match type_ {
+                Type::i(64) => arg,
+                Type::i(_) => *builder.build_sext(IntValue::try_from(arg).unwrap(), i64, c""),
+                Type::bool | Type::char => {
+                    *builder.build_zext(IntValue::try_from(arg).unwrap(), i64, c"")
+                }
+                _ => builder
+                    .build_extract_value(StructValue::try_from(arg).unwrap(), 0, c"")
+                    .unwrap(),
+            }
+        })
+        .collect();
+
+    let result = build_syscall(unit, number, &args);
+    builder.build_ret(result);
+
+    Ok(())
+}
+
+/// Generates `static_word`, which returns the address of a zero-initialized 8-byte global. Every
+/// function that uses this intrinsic gets its own global.
+fn add_static_word(
+    params_span: Span,
+    unit: &CodegenUnit<'_, '_>,
+    function_info: &FunctionInfo,
+    params: &[(S<&str>, Type)],
+) -> Result<(), Diagnostic> {
+    if !params.is_empty() {
+        return Err(codegen::error::invalid_intrinsic_params(params_span, "()"));
+    }
+
+    if function_info.signature.return_type != Type::i(64) {
+        return Err(codegen::error::invalid_intrinsic_ret_type(
+            params_span,
+            &Type::i(64),
+        ));
+    }
+
+    let i64 = unit.c.context.int_type(64);
+
+    let global = unit.module.add_global(*i64, c"");
+    global.set_initializer(Some(*i64.const_(0, false)));
+    global.set_linkage(Linkage::Private);
+    global.set_alignment(8);
+
+    let function = function_info.function;
+
+    let main_block = function.add_basic_block(c"");
+    unit.builder.position_at_end(main_block);
+
+    let address = unit.builder.build_ptr_to_int(global.as_ptr(), i64, c"");
+    unit.builder.build_ret(*address);
+
+    Ok(())
+}
+
+/// Gets a function from libc, declaring it if needed. This is only used in hosted mode
+fn libc_function<'ctx>(
+    unit: &CodegenUnit<'_, 'ctx>,
+    name: &str,
+    fn_type: FnType<'ctx>,
+) -> FnValue<'ctx> {
+    unit.module.get_function(name).unwrap_or_else(|| {
+        unit.module
+            .add_function(&CString::new(name).unwrap(), fn_type)
+    })
+}
+
+/// Gets one of the `[str]` globals that are set by `_start`, declaring it if needed
+fn process_global<'ctx>(unit: &CodegenUnit<'_, 'ctx>, name: &str) -> GlobalVariable<'ctx> {
+    unit.module.get_global(name).unwrap_or_else(|| {
+        unit.module
+            .add_global(*unit.c.core_types.slice, &CString::new(name).unwrap())
+    })
+}
+
+fn add_args(
+    params_span: Span,
+    unit: &CodegenUnit<'_, '_>,
+    function_info: &FunctionInfo,
+    params: &[(S<&str>, Type)],
+) -> Result<(), Diagnostic> {
+    if !params.is_empty() {
+        return Err(codegen::error::invalid_intrinsic_params(params_span, "()"));
+    }
+
+    let args_type = Type::Slice(Box::new(Type::str));
+
+    if function_info.signature.return_type != args_type {
+        return Err(codegen::error::invalid_intrinsic_ret_type(
+            params_span,
+            &args_type,
+        ));
+    }
+
+    let function = function_info.function;
+
+    let main_block = function.add_basic_block(c"");
+    unit.builder.position_at_end(main_block);
+
+    let args = process_global(unit, ARGS_GLOBAL);
+    let args = unit
+        .builder
+        .build_load(*unit.c.core_types.slice, args.as_ptr(), c"");
+
+    unit.builder.build_ret(args);
+
+    Ok(())
+}
+
+/// Looks up an environment variable. An empty string is returned if it is not set
+fn add_env_var(
+    params_span: Span,
+    unit: &CodegenUnit<'_, '_>,
+    function_info: &FunctionInfo,
+    params: &[(S<&str>, Type)],
+) -> Result<(), Diagnostic> {
+    if !matches!(params, [(_, Type::str)]) {
+        return Err(codegen::error::invalid_intrinsic_params(
+            params_span,
+            "(str)",
+        ));
+    }
 
-    let exit_code = unit.builder.build_zext(
-        IntValue::try_from(function.param(0).unwrap()).unwrap(),
-        i64,
+    if function_info.signature.return_type != Type::str {
+        return Err(codegen::error::invalid_intrinsic_ret_type(
+            params_span,
+            &Type::str,
+        ));
+    }
+
+    let context = unit.c.context;
+    let builder = &unit.builder;
+    let isize = unit.c.core_types.isize;
+    let str_type = unit.c.core_types.str;
+    let i8 = context.int_type(8);
+
+    let function = function_info.function;
+
+    let entry_block = function.add_basic_block(c"");
+    let loop_block = function.add_basic_block(c"");
+    let body_block = function.add_basic_block(c"");
+    let compare_block = function.add_basic_block(c"");
+    let next_block = function.add_basic_block(c"");
+    let found_block = function.add_basic_block(c"");
+    let not_found_block = function.add_basic_block(c"");
+
+    builder.position_at_end(entry_block);
+
+    let name = function.param(0).unwrap();
+    let name_len = builder
+        .build_extract_value(StructValue::try_from(name).unwrap(), 1, c"")
+        .unwrap();
+    let name_len = IntValue::try_from(name_len).unwrap();
+
+    let env = process_global(unit, ENV_GLOBAL);
+    let env = builder.build_load(*unit.c.core_types.slice, env.as_ptr(), c"");
+    let env = StructValue::try_from(env).unwrap();
+    let env_ptr = PtrValue::try_from(builder.build_extract_value(env, 0, c"").unwrap()).unwrap();
+    let env_len = IntValue::try_from(builder.build_extract_value(env, 1, c"").unwrap()).unwrap();
+
+    builder.build_br(loop_block);
+
+    // Every variable is checked for the form `{name}={value}` //
+
+    builder.position_at_end(loop_block);
+    let idx = builder.build_phi(*isize, c"");
+    let idx_val = IntValue::try_from(*idx).unwrap();
+    let done = builder.build_icmp(IntPredicate::EQ, idx_val, env_len, c"");
+    builder.build_cond_br(done, not_found_block, body_block);
+
+    builder.position_at_end(body_block);
+    let var = builder.build_gep(*str_type, env_ptr, &[idx_val], c"");
+    let var = StructValue::try_from(builder.build_load(*str_type, var, c"")).unwrap();
+    let var_ptr = PtrValue::try_from(builder.build_extract_value(var, 0, c"").unwrap()).unwrap();
+    let var_len = IntValue::try_from(builder.build_extract_value(var, 1, c"").unwrap()).unwrap();
+    let long_enough = builder.build_icmp(IntPredicate::ULT, name_len, var_len, c"");
+    builder.build_cond_br(long_enough, compare_block, next_block);
+
+    builder.position_at_end(compare_block);
+    let var_name = builder.build_insert_value(str_type.const_null(), *var_ptr, 0, c"");
+    let var_name = builder.build_insert_value(var_name, *name_len, 1, c"");
+    let same_name = builder.build_fn_call(unit.str_eq_fn(), &[*var_name, name], c"");
+    let separator = builder.build_gep(*i8, var_ptr, &[name_len], c"");
+    let separator = IntValue::try_from(builder.build_load(*i8, separator, c"")).unwrap();
+    let is_separator = builder.build_icmp(
+        IntPredicate::EQ,
+        separator,
+        i8.const_(u64::from(b'='), false),
         c"",
     );
+    let matches = builder.build_and(IntValue::try_from(same_name).unwrap(), is_separator, c"");
+    builder.build_cond_br(matches, found_block, next_block);
 
-    // do call //
+    builder.position_at_end(next_block);
+    let next_idx = builder.build_add(idx_val, isize.const_(1, false), c"");
+    builder.build_br(loop_block);
 
-    unit.builder.build_ptr_call(
-        syscall_type,
-        syscall,
-        &[*i64.const_(60, false), *exit_code],
-        c"",
+    idx.add_incoming(
+        &[*isize.const_(0, false), *next_idx],
+        &[entry_block, next_block],
     );
 
-    let zero = unit.c.core_types.unit.const_(&[]);
-    unit.builder.build_ret(*zero);
+    builder.position_at_end(found_block);
+    let value_start = builder.build_add(name_len, isize.const_(1, false), c"");
+    let value_ptr = builder.build_gep(*i8, var_ptr, &[value_start], c"");
+    let value_len = builder.build_sub(var_len, value_start, c"");
+    let value = builder.build_insert_value(str_type.const_null(), *value_ptr, 0, c"");
+    let value = builder.build_insert_value(value, *value_len, 1, c"");
+    builder.build_ret(*value);
+
+    builder.position_at_end(not_found_block);
+    builder.build_ret(*str_type.const_null());
 
     Ok(())
 }
@@ -1,9 +1,14 @@
 use std::{borrow::Borrow, collections::HashMap};
 
 use wllvm::{type_::StructType, value::FnValue};
+use wutil::Span;
 
 use crate::{
-    codegen::{self, types::Type},
+    codegen::{
+        self,
+        const_value::{ConstExpr, ConstValue},
+        types::Type,
+    },
     error_handling::{Diagnostic, Spanned},
     parser::ast,
     util::HashMapExt,
@@ -13,6 +18,10 @@ use crate::{
 pub struct FunctionSignature {
     pub params: Vec<Type>,
     pub return_type: Type,
+    /// The names of the parameter types and the return type as written, for diagnostics (see
+    /// [`Type::written_name`])
+    pub param_names: Vec<String>,
+    pub return_type_name: String,
 }
 
 #[derive(Clone, Debug)]
@@ -20,21 +29,72 @@ pub struct FunctionInfo<'ctx> {
     pub signature: FunctionSignature,
     pub function: FnValue<'ctx>,
     pub visibility: ast::Visibility,
+    /// Whether the function uses the C calling convention (ie it is an `extern` function)
+    pub c_abi: bool,
 }
 
 pub struct FieldInfo {
     pub name: String,
     pub ty: Type,
+    /// The name of the type as written, for diagnostics (see [`Type::written_name`])
+    pub type_name: String,
     pub line_no: u32,
+    pub span: Span,
+    pub visibility: ast::Visibility,
+    /// The index of the field in the LLVM struct body. This can differ from the declaration order
+    /// because fields are reordered to reduce padding
+    pub llvm_idx: u32,
+    /// The default value of the field as written in the struct declaration
+    pub default_expr: Option<Spanned<ConstExpr>>,
+    /// The evaluated default value. This is set by [`codegen::CodegenContext::generate_struct_bodies`]
+    pub default: Option<ConstValue>,
+}
+
+impl FieldInfo {
+    /// Checks that this field of the struct `struct_path` can be accessed from `crate_name`
+    pub fn check_visibility(
+        &self,
+        struct_path: &str,
+        crate_name: &str,
+        name: Spanned<&str>,
+    ) -> Result<(), Diagnostic> {
+        let struct_crate = struct_path.split("::").next().unwrap();
+
+        if self.visibility != ast::Visibility::Public && struct_crate != crate_name {
+            return Err(codegen::error::private_field(struct_path, name));
+        }
+
+        Ok(())
+    }
 }
 
 pub struct StructInfo<'ctx> {
     /// The LLVM representation of the type (or `None` if it is uninstantiable)
     pub llvm_type: Option<StructType<'ctx>>,
+    /// The fields of the struct in declaration order
     pub fields: Vec<FieldInfo>,
     pub packed: bool,
+    /// Whether the fields should be laid out in declaration order (`#[repr(C)]`)
+    pub repr_c: bool,
+    /// The alignment requested with `#[align(N)]`
+    pub explicit_align: Option<u32>,
+    /// The alignment of the struct in bytes. This can be larger than the alignment of the LLVM
+    /// type when `#[align(N)]` is used
+    pub alignment: u32,
     pub line_no: u32,
     pub file_no: usize,
+    pub visibility: ast::Visibility,
+    /// The path of the function set with `#[drop(function)]`
+    pub drop_fn: Option<String>,
+    /// Whether values of the struct are copied instead of moved (`#[copy]`)
+    pub copy: bool,
+}
+
+pub struct TypeAliasInfo {
+    /// The path of the aliased type. This is resolved relative to `crate_name` when the alias is used
+    pub target: Spanned<Vec<Spanned<String>>>,
+    pub crate_name: String,
+    pub visibility: ast::Visibility,
 }
 
 pub struct NameStore<'ctx> {
@@ -45,6 +105,7 @@ pub enum NameStoreEntry<'ctx> {
     Module(NameStore<'ctx>),
     Function(FunctionInfo<'ctx>),
     Struct(StructInfo<'ctx>),
+    TypeAlias(TypeAliasInfo),
 }
 
 impl<'ctx> NameStoreEntry<'ctx> {
@@ -63,6 +124,16 @@ impl<'ctx> NameStoreEntry<'ctx> {
             None
         }
     }
+
+    /// Gets the visibility of the item or `None` for modules
+    pub fn visibility(&self) -> Option<ast::Visibility> {
+        match self {
+            NameStoreEntry::Module(_) => None,
+            NameStoreEntry::Function(func) => Some(func.visibility),
+            NameStoreEntry::Struct(struct_) => Some(struct_.visibility),
+            NameStoreEntry::TypeAlias(alias) => Some(alias.visibility),
+        }
+    }
 }
 
 impl<'ctx> NameStore<'ctx> {
@@ -87,6 +158,14 @@ impl<'ctx> NameStore<'ctx> {
         self.add_item(key, NameStoreEntry::Struct(struct_))
     }
 
+    /// Returns false if the type alias already exists
+    pub fn add_type_alias<S>(&mut self, key: &[S], alias: TypeAliasInfo) -> bool
+    where
+        S: Borrow<str>,
+    {
+        self.add_item(key, NameStoreEntry::TypeAlias(alias))
+    }
+
     /// Returns false if the item already exists
     pub fn add_item<S>(&mut self, key: &[S], item: NameStoreEntry<'ctx>) -> bool
     where
@@ -164,6 +243,45 @@ impl<'ctx> NameStore<'ctx> {
         parent.get(funcname)
     }
 
+    pub fn get_item_from_string_mut(&mut self, key: &str) -> Option<&mut NameStoreEntry<'ctx>> {
+        let (parents, funcname) = key.rsplit_once("::").unwrap_or((&key[0..0], key));
+
+        let mut parent = &mut self.store;
+
+        for pmod in parents.split("::") {
+            match parent.get_mut(pmod) {
+                Some(NameStoreEntry::Module(store)) => parent = &mut store.store,
+                _ => return None,
+            }
+        }
+
+        parent.get_mut(funcname)
+    }
+
+    /// Gets the full paths of every struct in the store
+    pub fn struct_paths(&self) -> Vec<String> {
+        let mut paths = Vec::new();
+        self.push_struct_paths("", &mut paths);
+
+        paths
+    }
+
+    fn push_struct_paths(&self, prefix: &str, paths: &mut Vec<String>) {
+        for (name, item) in &self.store {
+            let path = if prefix.is_empty() {
+                name.clone()
+            } else {
+                format!("{prefix}::{name}")
+            };
+
+            match item {
+                NameStoreEntry::Module(store) => store.push_struct_paths(&path, paths),
+                NameStoreEntry::Struct(_) => paths.push(path),
+                _ => {}
+            }
+        }
+    }
+
     pub fn get_item_in_crate(
         &self,
         crate_name: &str,
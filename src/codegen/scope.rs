@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use wllvm::{
     debug_info::{DIFlags, DILocalScope},
-    value::FnValue,
+    value::{FnValue, PtrValue},
 };
 use wutil::Span;
 
@@ -22,13 +22,34 @@ pub use break_::BreakContext;
 pub struct ScopeVariable<'ctx> {
     pub value: GenericValue<'ctx>,
     pub name_span: Span,
+    /// Identifies the variable in move checking
+    pub id: usize,
+    /// A boolean that is cleared when the value is moved out of the variable. This is only
+    /// present if the value has to be dropped
+    pub drop_flag: Option<PtrValue<'ctx>>,
+}
+
+/// The way that control flow leaves a scope
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ScopeExit {
+    /// The end of the block is reached. Only the current scope is left
+    Fallthrough,
+    /// Every scope up to and including the innermost loop body is left
+    Break,
+    /// Every scope up to and including the function scope is left
+    Return,
 }
 
 pub struct Scope<'p, 'ctx> {
     parent: Option<&'p Scope<'p, 'ctx>>,
     variables: HashMap<String, ScopeVariable<'ctx>>,
+    /// The variables that have to be dropped when this scope is left along with their drop flags,
+    /// in declaration order. Unlike `variables`, this includes variables that have been shadowed
+    drops: Vec<(GenericValue<'ctx>, PtrValue<'ctx>)>,
     break_context: Option<&'p BreakContext<'ctx>>,
-    return_type: Option<Type>,
+    /// The return type of the function along with its name as written (see
+    /// [`Type::written_name`])
+    return_type: Option<(Type, String)>,
     di_scope: Option<DILocalScope<'ctx>>,
 }
 
@@ -37,6 +58,7 @@ impl<'ctx> Scope<'_, 'ctx> {
         Self {
             parent: None,
             variables: HashMap::new(),
+            drops: Vec::new(),
             break_context: None,
             return_type: None,
             di_scope: None,
@@ -72,14 +94,15 @@ impl<'p, 'ctx> Scope<'p, 'ctx> {
         Self {
             parent: Some(parent),
             variables: HashMap::new(),
+            drops: Vec::new(),
             break_context: None,
             return_type: None,
             di_scope: Some(di_scope),
         }
     }
 
-    pub fn with_return_type(mut self, return_type: Type) -> Self {
-        self.return_type = Some(return_type);
+    pub fn with_return_type(mut self, return_type: Type, name: String) -> Self {
+        self.return_type = Some((return_type, name));
         self
     }
 
@@ -108,10 +131,22 @@ impl<'p, 'ctx> Scope<'p, 'ctx> {
         self
     }
 
+    /// Stops the parameters of the function from being dropped. This is used for drop functions,
+    /// whose parameter is the value that is being dropped
+    pub fn without_param_drops(mut self) -> Self {
+        self.drops.clear();
+        self
+    }
+
     /// Creates a function scope for an uncallable function (ie one of its parameters is uninstantiable)
-    pub fn with_uninstatiable_params(mut self, params: &[(S<&str>, Type)]) -> Self {
+    pub fn with_uninstatiable_params(
+        mut self,
+        params: &[(S<&str>, Type)],
+        cu: &CodegenUnit<'_, 'ctx>,
+    ) -> Self {
         for param in params.iter() {
             self.create_unreachable_variable(
+                cu,
                 param.0,
                 GenericValue::RValue(RValue {
                     val: None,
@@ -194,25 +229,70 @@ impl<'p, 'ctx> Scope<'p, 'ctx> {
             _ => {}
         }
 
+        let id = cu.declare_variable();
+
+        let instantiated = match &value {
+            GenericValue::RValue(rvalue) => rvalue.val.is_some(),
+            GenericValue::MutValue(mut_value) => mut_value.ptr.is_some(),
+        };
+
+        let drop_flag = (instantiated && value.type_().needs_drop(cu.c)).then(|| {
+            let drop_flag = cu.generate_drop_flag();
+            self.drops.push((value.clone(), drop_flag));
+            drop_flag
+        });
+
         self.variables.insert(
             name.0.to_owned(),
             ScopeVariable {
                 value,
                 name_span: name.1,
+                id,
+                drop_flag,
             },
         );
     }
 
-    fn create_unreachable_variable(&mut self, name: S<&str>, value: GenericValue<'ctx>) {
+    fn create_unreachable_variable(
+        &mut self,
+        cu: &CodegenUnit<'_, 'ctx>,
+        name: S<&str>,
+        value: GenericValue<'ctx>,
+    ) {
         self.variables.insert(
             name.0.to_owned(),
             ScopeVariable {
                 value,
                 name_span: name.1,
+                id: cu.declare_variable(),
+                drop_flag: None,
             },
         );
     }
 
+    /// Drops the variables of every scope that is left by `exit`, innermost first and in reverse
+    /// declaration order. Variables that have been moved are skipped using their drop flags.
+    pub fn generate_drops(&self, cu: &CodegenUnit<'_, 'ctx>, exit: ScopeExit) {
+        let mut scope = self;
+
+        loop {
+            for (value, drop_flag) in scope.drops.iter().rev() {
+                cu.generate_flagged_drop(value, *drop_flag);
+            }
+
+            let last = match exit {
+                ScopeExit::Fallthrough => true,
+                ScopeExit::Break => scope.break_context.is_some(),
+                ScopeExit::Return => scope.return_type.is_some(),
+            };
+
+            match scope.parent {
+                Some(parent) if !last => scope = parent,
+                _ => break,
+            }
+        }
+    }
+
     pub fn get_variable(&self, name: &str) -> Option<&ScopeVariable<'ctx>> {
         self.variables
             .get(name)
@@ -223,9 +303,11 @@ impl<'p, 'ctx> Scope<'p, 'ctx> {
         self.break_context.or_else(|| self.parent?.get_break())
     }
 
-    pub fn get_return_type(&self) -> Option<&Type> {
+    /// Gets the return type of the function along with its name as written
+    pub fn get_return_type(&self) -> Option<(&Type, &str)> {
         self.return_type
             .as_ref()
+            .map(|(type_, name)| (type_, name.as_str()))
             .or_else(|| self.parent?.get_return_type())
     }
 }
@@ -6,9 +6,10 @@ use std::{
 use wllvm::debug_info::DIType;
 
 use crate::{
-    codegen::{codegen_unit::CodegenUnit, error, CodegenContext},
-    error_handling::{Diagnostic, Spanned as S},
+    codegen::{codegen_unit::CodegenUnit, error, namestore::NameStoreEntry, CodegenContext},
+    error_handling::{self, Diagnostic, Spanned as S},
     parser::ast,
+    util::MaybeVec,
 };
 
 #[derive(PartialEq, Eq, Clone, Debug)]
@@ -19,6 +20,8 @@ pub enum Type {
     unit,
     never,
     bool,
+    char,
+    Slice(Box<Type>),
     Struct { path: String },
 }
 
@@ -29,6 +32,8 @@ impl Display for Type {
             Type::str => "str".into(),
             Type::unit => "()".into(),
             Type::bool => "bool".into(),
+            Type::char => "char".into(),
+            Type::Slice(element) => format!("[{element}]").into(),
             Type::Struct { path } => Cow::Borrowed(path),
             Type::never => "!".into(),
         };
@@ -49,27 +54,81 @@ impl Type {
         cc: &CodegenContext,
         crate_name: &str,
         type_: &S<ast::Path>,
+    ) -> Result<Self, Diagnostic> {
+        Self::new_with_aliases(cc, crate_name, type_, &mut Vec::new())
+    }
+
+    /// Gets the name that the type written as `path` is shown with in diagnostics. Type aliases
+    /// are resolved when types are created, so this is the alias if `path` names one and the type
+    /// itself otherwise.
+    pub fn written_name(&self, cc: &CodegenContext, crate_name: &str, path: &ast::Path) -> String {
+        let is_alias =
+            |item: Result<&NameStoreEntry, _>| matches!(item, Ok(NameStoreEntry::TypeAlias(_)));
+
+        match (self, &**path) {
+            (Type::Slice(element_type), [S("[]", _), element @ ..]) => {
+                let mut element_path = MaybeVec::new();
+                for &segment in element {
+                    element_path.push(segment);
+                }
+
+                format!(
+                    "[{}]",
+                    element_type.written_name(cc, crate_name, &element_path)
+                )
+            }
+            (_, [name]) if is_alias(cc.name_store.get_item_in_crate(crate_name, *name)) => {
+                name.to_string()
+            }
+            (_, [_, _, ..]) if is_alias(cc.name_store.get_item(path)) => path
+                .iter()
+                .map(|segment| **segment)
+                .collect::<Vec<_>>()
+                .join("::"),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Resolves a type path. `aliases` contains the paths of the type aliases currently being
+    /// resolved and is used to detect aliases that are defined in terms of themselves
+    fn new_with_aliases(
+        cc: &CodegenContext,
+        crate_name: &str,
+        type_: &S<ast::Path>,
+        aliases: &mut Vec<String>,
     ) -> Result<Self, Diagnostic> {
         Ok(match &***type_ {
             [S("str", _)] => Self::str,
             [S("()", _)] => Self::unit,
             [S("!", _)] => Self::never,
             [S("bool", _)] => Self::bool,
+            [S("char", _)] => Self::char,
+            [S("[]", _), element @ ..] => {
+                let mut element_path = MaybeVec::new();
+                for &segment in element {
+                    element_path.push(segment);
+                }
+
+                let element_span = error_handling::span_of(element).unwrap();
+
+                Self::Slice(Box::new(Self::new_with_aliases(
+                    cc,
+                    crate_name,
+                    &S(element_path, element_span),
+                    aliases,
+                )?))
+            }
             [type_] => {
                 if let Some(num) = type_.strip_prefix("i").and_then(|n| n.parse::<u32>().ok()) {
                     Self::i(num)
                 } else {
-                    cc.name_store
-                        .get_item_in_crate(crate_name, *type_)?
-                        .as_struct()
-                        .ok_or_else(|| error::not_type(*type_))?;
-
-                    return Ok(Self::Struct {
-                        path: format!("{crate_name}::{}", **type_),
-                    });
+                    let item = cc.name_store.get_item_in_crate(crate_name, *type_)?;
+                    let path = format!("{crate_name}::{}", **type_);
+
+                    return Self::from_item(cc, item, S(path, type_.1), aliases);
                 }
             }
-            _ => {
+            [parent_crate, .., item_name] => {
                 let mut path = String::new();
 
                 for (i, &segment) in type_.iter().enumerate() {
@@ -80,16 +139,61 @@ impl Type {
                     path.push_str(*segment);
                 }
 
-                cc.name_store
-                    .get_item(type_)?
-                    .as_struct()
-                    .ok_or_else(|| error::not_type(S(&path, type_.1)))?;
+                let item = cc.name_store.get_item(type_)?;
+
+                if item.visibility() == Some(ast::Visibility::Private)
+                    && **parent_crate != crate_name
+                {
+                    return Err(error::private_type(*parent_crate, *item_name));
+                }
 
-                Self::Struct { path }
+                return Self::from_item(cc, item, S(path, type_.1), aliases);
             }
+            [] => unreachable!(),
         })
     }
 
+    fn from_item(
+        cc: &CodegenContext,
+        item: &NameStoreEntry,
+        path: S<String>,
+        aliases: &mut Vec<String>,
+    ) -> Result<Self, Diagnostic> {
+        match item {
+            NameStoreEntry::Struct(_) => Ok(Self::Struct { path: path.0 }),
+            NameStoreEntry::TypeAlias(alias) => {
+                if let Some(idx) = aliases.iter().position(|a| *a == *path) {
+                    return Err(error::recursive_type_alias(
+                        S(&path, path.1),
+                        &aliases[idx..],
+                    ));
+                }
+
+                let mut target = MaybeVec::new();
+                for segment in &*alias.target {
+                    target.push(S(&*segment.0, segment.1));
+                }
+
+                aliases.push(path.0);
+                let type_ = Self::new_with_aliases(
+                    cc,
+                    &alias.crate_name,
+                    &S(target, alias.target.1),
+                    aliases,
+                )?;
+                aliases.pop();
+
+                Ok(type_)
+            }
+            _ => Err(error::not_type(S(&path, path.1))),
+        }
+    }
+
+    /// Creates a byte slice type (`[i8]`)
+    pub fn bytes() -> Self {
+        Self::Slice(Box::new(Self::i(8)))
+    }
+
     /// Gets the underlying LLVM type or `None` if the type is uninstantiable
     pub fn llvm_type<'ctx>(&self, context: &CodegenContext<'ctx>) -> Option<wllvm::Type<'ctx>> {
         Some(match *self {
@@ -97,6 +201,8 @@ impl Type {
             Type::str => context.core_types.str.into(),
             Type::unit => context.core_types.unit.into(),
             Type::bool => context.core_types.bool.into(),
+            Type::char => context.core_types.char.into(),
+            Type::Slice(_) => context.core_types.slice.into(),
             Type::Struct { ref path } => {
                 let struct_info = context
                     .name_store
@@ -111,6 +217,77 @@ impl Type {
         })
     }
 
+    /// Gets the alignment of the type in bytes. Unlike the alignment of the LLVM type, this takes
+    /// `#[align(N)]` into account
+    pub fn alignment(&self, context: &CodegenContext) -> u32 {
+        if let Type::Struct { path } = self {
+            return context
+                .name_store
+                .get_item_from_string(path)
+                .unwrap()
+                .as_struct()
+                .unwrap()
+                .alignment;
+        }
+
+        self.llvm_type(context)
+            .map_or(1, |t| t.alignment(&context.target_data))
+    }
+
+    /// Checks if values of this type are copied instead of moved. Every type other than structs is
+    /// copied; structs are only copied if they are marked with `#[copy]`
+    pub fn is_copy(&self, context: &CodegenContext) -> bool {
+        let Type::Struct { path } = self else {
+            return true;
+        };
+
+        context
+            .name_store
+            .get_item_from_string(path)
+            .unwrap()
+            .as_struct()
+            .unwrap()
+            .copy
+    }
+
+    /// Checks if values of this type have to be cleaned up when they go out of scope, ie if it
+    /// is a struct with a drop function or it contains one
+    pub fn needs_drop(&self, context: &CodegenContext) -> bool {
+        let Type::Struct { path } = self else {
+            return false;
+        };
+
+        let struct_info = context
+            .name_store
+            .get_item_from_string(path)
+            .unwrap()
+            .as_struct()
+            .unwrap();
+
+        struct_info.drop_fn.is_some()
+            || struct_info
+                .fields
+                .iter()
+                .any(|field| field.ty.needs_drop(context))
+    }
+
+    /// Checks if values of this type can be compared with `==` and `!=`
+    pub fn has_equality(&self, context: &CodegenContext) -> bool {
+        match self {
+            Type::Slice(_) => false,
+            Type::Struct { path } => context
+                .name_store
+                .get_item_from_string(path)
+                .unwrap()
+                .as_struct()
+                .unwrap()
+                .fields
+                .iter()
+                .all(|field| field.ty.has_equality(context)),
+            _ => true,
+        }
+    }
+
     pub fn get_dwarf_type<'ctx>(&self, cu: &CodegenUnit<'_, 'ctx>) -> DIType<'ctx> {
         cu.debug_context.get_type(self, cu)
     }
@@ -1,4 +1,9 @@
-use wllvm::{builder::IntPredicate, value::ValueEnum, Builder};
+use wllvm::{
+    builder::IntPredicate,
+    type_::IntType,
+    value::{IntValue, ValueEnum},
+    Builder,
+};
 use wutil::Span;
 
 use crate::{
@@ -75,6 +80,7 @@ impl<'ctx> MutValue<'ctx> {
         };
 
         let ptr = cu.builder.build_alloca(llvm_type, c"");
+        ptr.set_alignment(rvalue.type_.alignment(cu.c));
         cu.builder.build_store(val, ptr);
 
         Self {
@@ -95,8 +101,9 @@ impl<'ctx> RValue<'ctx> {
         let builder = &cu.builder;
         match self.type_ {
             Type::i(n) => self.generate_operation_int(n, builder, lhs_span, opcode, rhs),
-            Type::unit | Type::str | Type::Struct { .. } => {
-                Err(error::undefined_operator(opcode, lhs_span, &self.type_))
+            Type::char => self.generate_operation_char(builder, lhs_span, opcode, rhs),
+            Type::unit | Type::str | Type::Slice(_) | Type::Struct { .. } => {
+                self.generate_comparison(cu, lhs_span, opcode, rhs)
             }
             Type::bool => {
                 if !rhs.type_.is(&Type::bool) {
@@ -136,6 +143,205 @@ impl<'ctx> RValue<'ctx> {
         }
     }
 
+    /// Generates `==` and `!=` for types without other operators. `str`s additionally support
+    /// `<`, `>`, `<=` and `>=`.
+    fn generate_comparison(
+        &self,
+        cu: &CodegenUnit<'_, 'ctx>,
+        lhs_span: Span,
+        opcode: OpCode,
+        rhs: &S<RValue<'ctx>>,
+    ) -> Result<RValue<'ctx>, Diagnostic> {
+        let defined = match opcode {
+            OpCode::Equal | OpCode::NotEqual => self.type_.has_equality(cu.c),
+            OpCode::Greater | OpCode::Less | OpCode::GreaterEqual | OpCode::LessEqual => {
+                self.type_ == Type::str
+            }
+            _ => false,
+        };
+
+        if !defined {
+            let is_struct = matches!(self.type_, Type::Struct { .. });
+
+            if let Some(trait_) = opcode.trait_name().filter(|_| is_struct) {
+                return Err(error::undefined_struct_operator(
+                    opcode,
+                    lhs_span,
+                    &self.type_,
+                    trait_,
+                ));
+            }
+
+            return Err(error::undefined_operator(opcode, lhs_span, &self.type_));
+        }
+
+        if !rhs.type_.is(&self.type_) {
+            return Err(error::unexpected_type(rhs.1, &self.type_, &rhs.type_));
+        }
+
+        let Some((lhs_val, rhs_val)) = self.val.zip(rhs.val) else {
+            return Ok(RValue {
+                val: None,
+                type_: Type::bool,
+            });
+        };
+
+        let builder = &cu.builder;
+
+        // `a > b` is `b < a`, and `a >= b` is `!(a < b)`
+        let val = match opcode {
+            OpCode::Equal => cu.generate_equality(builder, &self.type_, lhs_val, rhs_val),
+            OpCode::NotEqual => {
+                let eq = cu.generate_equality(builder, &self.type_, lhs_val, rhs_val);
+                builder.build_not(eq, c"")
+            }
+            OpCode::Less => cu.generate_str_less_than(lhs_val, rhs_val),
+            OpCode::Greater => cu.generate_str_less_than(rhs_val, lhs_val),
+            OpCode::GreaterEqual => {
+                let lt = cu.generate_str_less_than(lhs_val, rhs_val);
+                builder.build_not(lt, c"")
+            }
+            OpCode::LessEqual => {
+                let gt = cu.generate_str_less_than(rhs_val, lhs_val);
+                builder.build_not(gt, c"")
+            }
+            _ => unreachable!(),
+        };
+
+        Ok(Self {
+            type_: Type::bool,
+            val: Some(*val),
+        })
+    }
+
+    fn generate_operation_char(
+        &self,
+        builder: &Builder<'ctx>,
+        lhs_span: Span,
+        opcode: OpCode,
+        rhs: &S<RValue<'ctx>>,
+    ) -> Result<RValue<'ctx>, Diagnostic> {
+        if !rhs.type_.is(&Type::char) {
+            return Err(error::unexpected_type(rhs.1, &Type::char, &rhs.type_));
+        }
+
+        // Characters are unicode scalar values, so they are compared as unsigned integers
+        let predicate = match opcode {
+            OpCode::Equal => IntPredicate::EQ,
+            OpCode::NotEqual => IntPredicate::NE,
+            OpCode::Greater => IntPredicate::UGT,
+            OpCode::Less => IntPredicate::ULT,
+            OpCode::GreaterEqual => IntPredicate::UGE,
+            OpCode::LessEqual => IntPredicate::ULE,
+            _ => return Err(error::undefined_operator(opcode, lhs_span, &self.type_)),
+        };
+
+        let Some((lhs_val, rhs_val)) = self.val.zip(rhs.val) else {
+            return Ok(RValue {
+                val: None,
+                type_: Type::bool,
+            });
+        };
+
+        let Some((ValueEnum::IntValue(lhs), ValueEnum::IntValue(rhs))) =
+            lhs_val.downcast().zip(rhs_val.downcast())
+        else {
+            unreachable!();
+        };
+
+        Ok(Self {
+            type_: Type::bool,
+            val: Some(*builder.build_icmp(predicate, lhs, rhs, c"")),
+        })
+    }
+
+    /// Converts `self` into `target` (`as` expressions).
+    ///
+    /// Integers are sign-extended or truncated, while `char`s and `bool`s are zero-extended or
+    /// truncated. Converting an integer that isn't a unicode scalar value into a `char` aborts the
+    /// program.
+    pub fn generate_cast(
+        &self,
+        cu: &CodegenUnit<'_, 'ctx>,
+        span: Span,
+        target: S<&Type>,
+    ) -> Result<Self, Diagnostic> {
+        let signed = match (&self.type_, *target) {
+            (from, to) if from.is(to) => {
+                return Ok(Self {
+                    val: self.val,
+                    type_: to.clone(),
+                })
+            }
+            (Type::i(_), Type::i(_) | Type::char) => true,
+            (Type::char | Type::bool, Type::i(_)) => false,
+            _ => return Err(error::invalid_cast(span, &self.type_, target)),
+        };
+
+        let Some(val) = self.val else {
+            return Ok(Self {
+                val: None,
+                type_: target.0.clone(),
+            });
+        };
+
+        let Ok(val) = IntValue::try_from(val) else {
+            unreachable!()
+        };
+
+        let Some(Ok(target_type)) = target.llvm_type(cu.c).map(IntType::try_from) else {
+            unreachable!()
+        };
+
+        let from_width = val.type_().width();
+        let to_width = target_type.width();
+
+        let converted = if from_width > to_width {
+            cu.builder.build_trunc(val, target_type, c"")
+        } else if from_width == to_width {
+            val
+        } else if signed {
+            cu.builder.build_sext(val, target_type, c"")
+        } else {
+            cu.builder.build_zext(val, target_type, c"")
+        };
+
+        // Negative values, surrogates and values above U+10FFFF are rejected. Wider integers are
+        // checked before they are truncated so that they cannot wrap into range.
+        if *target.0 == Type::char {
+            let checked = if from_width > to_width {
+                val
+            } else {
+                converted
+            };
+            let int_type = checked.type_();
+
+            let below_surrogates = cu.builder.build_icmp(
+                IntPredicate::ULT,
+                checked,
+                int_type.const_(0xD800, false),
+                c"",
+            );
+
+            let offset = cu
+                .builder
+                .build_sub(checked, int_type.const_(0xE000, false), c"");
+            let above_surrogates = cu.builder.build_icmp(
+                IntPredicate::ULE,
+                offset,
+                int_type.const_(0x10FFFF - 0xE000, false),
+                c"",
+            );
+
+            cu.generate_assertion(cu.builder.build_or(below_surrogates, above_surrogates, c""));
+        }
+
+        Ok(Self {
+            val: Some(*converted),
+            type_: target.0.clone(),
+        })
+    }
+
     fn generate_operation_int(
         &self,
         bits: u32,
@@ -8,7 +8,7 @@ use wutil::prelude::*;
 mod error;
 mod token;
 
-pub use token::Token;
+pub use token::{Number, Token};
 use wutil::Span;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -18,6 +18,16 @@ pub enum BracketType {
     Curly,
 }
 
+/// The kind of a string or character literal. This determines which characters and escape
+/// sequences are allowed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum LiteralKind {
+    /// A UTF-8 string (`"foo"`) or character (`'a'`)
+    Str,
+    /// A byte string (`b"foo"`)
+    Byte,
+}
+
 #[derive(Clone)]
 pub struct Lexer<'a> {
     input: &'a str,
@@ -44,6 +54,16 @@ impl<'a> Iterator for Lexer<'a> {
                 continue;
             }
 
+            if char.is_ascii_digit() {
+                return Some(self.lex_number(byte_index, char));
+            }
+
+            if char == 'r' || char == 'b' {
+                if let Some(string) = self.try_lex_prefixed_string(byte_index, char) {
+                    return Some(string);
+                }
+            }
+
             if char.is_ascii_alphanumeric() || char == '_' {
                 let ident_span = self.lex_ident(byte_index);
 
@@ -54,7 +74,11 @@ impl<'a> Iterator for Lexer<'a> {
             }
 
             if char == '"' {
-                return Some(self.lex_string(byte_index));
+                return Some(self.lex_string(byte_index, LiteralKind::Str));
+            }
+
+            if char == '\'' {
+                return Some(self.lex_char(byte_index));
             }
 
             if char == '/' {
@@ -130,7 +154,112 @@ impl<'a> Lexer<'a> {
         (ident_start..ident_end).into()
     }
 
-    fn lex_string(&mut self, string_start: usize) -> Result<Spanned<Token<'a>>, Diagnostic> {
+    fn lex_number(
+        &mut self,
+        number_start: usize,
+        first_char: char,
+    ) -> Result<Spanned<Token<'a>>, Diagnostic> {
+        let mut radix = 10;
+
+        if first_char == '0' {
+            if let Some((_, prefix @ ('x' | 'o' | 'b'))) = self.chars.clone().next() {
+                self.chars.next();
+
+                radix = match prefix {
+                    'x' => 16,
+                    'o' => 8,
+                    _ => 2,
+                };
+            }
+        }
+
+        let digits_start = if radix == 10 {
+            number_start
+        } else {
+            self.chars.offset()
+        };
+
+        let mut is_float = false;
+        let mut has_exponent = false;
+        let mut has_digits = radix == 10;
+
+        loop {
+            let mut lookahead = self.chars.clone();
+            let Some((byte_index, char)) = lookahead.next() else {
+                break;
+            };
+
+            let next_char = lookahead.clone().next().map(|(_, c)| c);
+
+            if char == '_' {
+                self.chars.next();
+            } else if char.is_digit(radix) {
+                self.chars.next();
+                has_digits = true;
+            } else if char.is_ascii_digit() {
+                let span = Span::at(byte_index).with_len(1);
+
+                return Err(error::invalid_digit(
+                    Spanned(&self.input[span], span),
+                    radix,
+                ));
+            } else if radix == 10
+                && char == '.'
+                && !is_float
+                && next_char.is_some_and(|c| c.is_ascii_digit())
+            {
+                self.chars.next();
+                is_float = true;
+            } else if radix == 10 && matches!(char, 'e' | 'E') && !has_exponent {
+                let exponent_digit = match next_char {
+                    Some('+' | '-') => lookahead.nth(1).map(|(_, c)| c),
+                    c => c,
+                };
+
+                if !exponent_digit.is_some_and(|c| c.is_ascii_digit()) {
+                    break;
+                }
+
+                if matches!(next_char, Some('+' | '-')) {
+                    self.chars.next();
+                }
+
+                self.chars.next();
+                is_float = true;
+                has_exponent = true;
+            } else {
+                break;
+            }
+        }
+
+        let digits_end = self.chars.offset();
+        let suffix_span = self.lex_ident(digits_end);
+        let number_span = Span::at(number_start).with_end(suffix_span.end);
+
+        if !has_digits {
+            return Err(error::missing_digits(Spanned(
+                &self.input[number_span],
+                number_span,
+            )));
+        }
+
+        Ok(Spanned(
+            Token::Number(Number {
+                digits: &self.input[digits_start..digits_end],
+                radix,
+                suffix: Some(&self.input[suffix_span]).filter(|s| !s.is_empty()),
+                is_float,
+            }),
+            number_span,
+        ))
+    }
+
+    /// Lexes a string literal. The opening quote (and any prefix) must already be consumed.
+    fn lex_string(
+        &mut self,
+        string_start: usize,
+        kind: LiteralKind,
+    ) -> Result<Spanned<Token<'a>>, Diagnostic> {
         let mut string = String::new();
         let string_end;
 
@@ -145,32 +274,255 @@ impl<'a> Lexer<'a> {
                     break;
                 }
                 '\\' => {
-                    let Some((byte_index, char)) = self.chars.next() else {
+                    let Some(char_to_add) = self.lex_escape(byte_index, kind)? else {
                         continue; // Triggers an "unclosed string" error
                     };
 
-                    let char_to_add = match char {
-                        'n' => '\n',
-                        'r' => '\r',
-                        '\\' => '\\',
-                        '"' => '"',
-                        _ => {
-                            let span = self.input.char_span(byte_index).unwrap();
+                    string.push(char_to_add);
+                }
+                _ => {
+                    self.check_literal_char(byte_index, char, kind)?;
+                    string.push(char);
+                }
+            }
+        }
 
-                            return Err(error::invalid_escape(Spanned(&self.input[span], span)));
-                        }
-                    };
+        let span = Span::at(string_start).with_end(string_end + 1);
 
-                    string.push(char_to_add);
+        Ok(Spanned(Self::string_token(string, kind), span))
+    }
+
+    /// Lexes a raw string literal (eg `r#"foo"#`). The `r` prefix (and any other prefix) must
+    /// already be consumed.
+    fn lex_raw_string(
+        &mut self,
+        string_start: usize,
+        kind: LiteralKind,
+    ) -> Result<Spanned<Token<'a>>, Diagnostic> {
+        let num_hashes = self.chars.by_ref().take_while(|(_, c)| *c == '#').count();
+
+        let content_start = self.chars.offset();
+        let mut string = String::new();
+
+        loop {
+            let Some((byte_index, char)) = self.chars.next() else {
+                return Err(error::unclosed_string(Span::at(string_start)));
+            };
+
+            if char == '"' && self.input[byte_index + 1..].starts_with(&"#".repeat(num_hashes)) {
+                self.chars.by_ref().take(num_hashes).for_each(|_| {});
+
+                string.push_str(&self.input[content_start..byte_index]);
+
+                let span = Span::at(string_start).with_end(self.chars.offset());
+
+                return Ok(Spanned(Self::string_token(string, kind), span));
+            }
+
+            self.check_literal_char(byte_index, char, kind)?;
+        }
+    }
+
+    fn string_token(string: String, kind: LiteralKind) -> Token<'a> {
+        match kind {
+            LiteralKind::Str => Token::StringLiteral(string),
+            LiteralKind::Byte => {
+                Token::ByteStringLiteral(string.chars().map(|c| c as u8).collect())
+            }
+        }
+    }
+
+    /// Checks that a non-escaped character may appear in a literal
+    fn check_literal_char(
+        &self,
+        byte_index: usize,
+        char: char,
+        kind: LiteralKind,
+    ) -> Result<(), Diagnostic> {
+        if kind == LiteralKind::Byte && !char.is_ascii() {
+            let span = self.input.char_span(byte_index).unwrap();
+
+            return Err(error::non_ascii_byte(Spanned(&self.input[span], span)));
+        }
+
+        Ok(())
+    }
+
+    fn lex_char(&mut self, char_start: usize) -> Result<Spanned<Token<'a>>, Diagnostic> {
+        let Some((byte_index, char)) = self.chars.next() else {
+            return Err(error::unclosed_char(Span::at(char_start)));
+        };
+
+        let char = match char {
+            '\\' => self
+                .lex_escape(byte_index, LiteralKind::Str)?
+                .ok_or_else(|| error::unclosed_char(Span::at(char_start)))?,
+            '\'' => {
+                return Err(error::empty_char(
+                    Span::at(char_start).with_end(byte_index + 1),
+                ))
+            }
+            '\n' => return Err(error::unclosed_char(Span::at(char_start))),
+            _ => char,
+        };
+
+        let Some((char_end, '\'')) = self.chars.next() else {
+            return Err(error::unclosed_char(Span::at(char_start)));
+        };
+
+        Ok(Spanned(
+            Token::CharLiteral(char),
+            Span::at(char_start).with_end(char_end + 1),
+        ))
+    }
+
+    /// Lexes the escape sequence following a `\`. Returns `None` if the input ends.
+    ///
+    /// For byte literals, `\xNN` escapes above `\x7F` are returned as the character with the same
+    /// value.
+    fn lex_escape(
+        &mut self,
+        backslash: usize,
+        kind: LiteralKind,
+    ) -> Result<Option<char>, Diagnostic> {
+        let Some((_, char)) = self.chars.next() else {
+            return Ok(None);
+        };
+
+        Ok(Some(match char {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            'x' => return self.lex_hex_escape(backslash, kind),
+            'u' if kind == LiteralKind::Byte => {
+                // The whole escape is lexed so that the error points at all of it
+                if self.lex_unicode_escape(backslash)?.is_none() {
+                    return Ok(None);
                 }
-                _ => string.push(char),
+
+                return Err(error::unicode_escape_in_byte_string(
+                    Span::at(backslash).with_end(self.chars.offset()),
+                ));
             }
+            'u' => return self.lex_unicode_escape(backslash),
+            _ => {
+                let span = Span::at(backslash).with_end(self.chars.offset());
+
+                return Err(error::invalid_escape(Spanned(&self.input[span], span)));
+            }
+        }))
+    }
+
+    /// Lexes a `\xNN` escape. The `\x` must already be consumed.
+    fn lex_hex_escape(
+        &mut self,
+        backslash: usize,
+        kind: LiteralKind,
+    ) -> Result<Option<char>, Diagnostic> {
+        let mut value = 0;
+
+        for _ in 0..2 {
+            let Some((_, char)) = self.chars.next() else {
+                return Ok(None);
+            };
+
+            let Some(digit) = char.to_digit(16) else {
+                return Err(error::invalid_hex_escape(
+                    Span::at(backslash).with_end(self.chars.offset()),
+                ));
+            };
+
+            value = value * 16 + digit;
+        }
+
+        let span = Span::at(backslash).with_end(self.chars.offset());
+
+        if kind == LiteralKind::Str && value > 0x7F {
+            return Err(error::out_of_range_hex_escape(Spanned(
+                &self.input[span],
+                span,
+            )));
+        }
+
+        Ok(char::from_u32(value))
+    }
+
+    /// Lexes a `\u{NNNN}` escape. The `\u` must already be consumed.
+    fn lex_unicode_escape(&mut self, backslash: usize) -> Result<Option<char>, Diagnostic> {
+        let Some((_, '{')) = self.chars.next() else {
+            return Err(error::invalid_unicode_escape(
+                Span::at(backslash).with_end(self.chars.offset()),
+            ));
+        };
+
+        let mut value: u32 = 0;
+        let mut num_digits = 0;
+
+        loop {
+            let Some((_, char)) = self.chars.next() else {
+                return Ok(None);
+            };
+
+            if char == '}' && num_digits > 0 {
+                break;
+            }
+
+            if char == '_' && num_digits > 0 {
+                continue;
+            }
+
+            let Some(digit) = char.to_digit(16).filter(|_| num_digits < 6) else {
+                return Err(error::invalid_unicode_escape(
+                    Span::at(backslash).with_end(self.chars.offset()),
+                ));
+            };
+
+            value = value * 16 + digit;
+            num_digits += 1;
         }
 
-        return Ok(Spanned(
-            Token::StringLiteral(string),
-            Span::at(string_start).with_end(string_end + 1),
-        ));
+        let span = Span::at(backslash).with_end(self.chars.offset());
+
+        char::from_u32(value)
+            .map(Some)
+            .ok_or_else(|| error::invalid_unicode_char(Spanned(&self.input[span], span)))
+    }
+
+    /// Lexes a prefixed string literal (eg `r"foo"`, `b"foo"` or `br#"foo"#`) if there is one
+    fn try_lex_prefixed_string(
+        &mut self,
+        string_start: usize,
+        first_char: char,
+    ) -> Option<Result<Spanned<Token<'a>>, Diagnostic>> {
+        let mut chars = self.chars.clone();
+
+        let (kind, raw) = match (first_char, chars.next()?.1) {
+            ('b', '"') => (LiteralKind::Byte, false),
+            ('b', 'r') => (LiteralKind::Byte, true),
+            ('r', '"' | '#') => {
+                chars = self.chars.clone();
+                (LiteralKind::Str, true)
+            }
+            _ => return None,
+        };
+
+        if raw {
+            // A raw string prefix must be followed by `#`s and then a `"`
+            let mut hashes = chars.clone().skip_while(|(_, c)| *c == '#');
+            if hashes.next()?.1 != '"' {
+                return None;
+            }
+
+            self.chars = chars;
+            Some(self.lex_raw_string(string_start, kind))
+        } else {
+            self.chars = chars;
+            Some(self.lex_string(string_start, kind))
+        }
     }
 
     fn lex_symbol(
@@ -228,6 +580,7 @@ impl<'a> Lexer<'a> {
             ('>', '=') => T!(">="),
             ('<', '=') => T!("<="),
             (':', ':') => T!("::"),
+            ('.', '.') => T!(".."),
             _ => return None,
         };
 
@@ -19,6 +19,20 @@ pub fn unclosed_string(span: Span) -> Diagnostic {
     }
 }
 
+pub fn unclosed_char(span: Span) -> Diagnostic {
+    d! {
+        "Unclosed character literal",
+        [Hint::new_error("character literal starts here", span)],
+    }
+}
+
+pub fn empty_char(span: Span) -> Diagnostic {
+    d! {
+        "Empty character literal",
+        [Hint::new_error("", span)],
+    }
+}
+
 pub fn unclosed_comment(span: Span) -> Diagnostic {
     d! {
         "Unclosed comment",
@@ -28,7 +42,63 @@ pub fn unclosed_comment(span: Span) -> Diagnostic {
 
 pub fn invalid_escape(seq: Spanned<&str>) -> Diagnostic {
     d! {
-        format!("Invalid escape sequence \"\\{}\"", *seq),
+        format!("Invalid escape sequence \"{}\"", *seq),
         [Hint::new_error("", seq.1)],
     }
 }
+
+pub fn invalid_hex_escape(span: Span) -> Diagnostic {
+    d! {
+        "Invalid hex escape; expected two hexadecimal digits (eg `\\x41`)",
+        [Hint::new_error("", span)],
+    }
+}
+
+pub fn out_of_range_hex_escape(seq: Spanned<&str>) -> Diagnostic {
+    d! {
+        format!("Hex escape \"{}\" is out of range", *seq),
+        [Hint::new_error("must be at most `\\x7F`; use `\\u{...}` for other characters", seq.1)],
+    }
+}
+
+pub fn invalid_unicode_escape(span: Span) -> Diagnostic {
+    d! {
+        "Invalid unicode escape; expected one to six hexadecimal digits (eg `\\u{1F600}`)",
+        [Hint::new_error("", span)],
+    }
+}
+
+pub fn invalid_unicode_char(seq: Spanned<&str>) -> Diagnostic {
+    d! {
+        format!("Unicode escape \"{}\" is not a valid character", *seq),
+        [Hint::new_error("", seq.1)],
+    }
+}
+
+pub fn unicode_escape_in_byte_string(span: Span) -> Diagnostic {
+    d! {
+        "Unicode escapes cannot be used in byte strings",
+        [Hint::new_error("", span)],
+    }
+}
+
+pub fn non_ascii_byte(char: Spanned<&str>) -> Diagnostic {
+    d! {
+        format!("Non-ASCII character `{}` in byte string", *char),
+        [Hint::new_error("try using a hex escape (eg `\\xFF`) instead", char.1)],
+    }
+}
+
+pub fn invalid_digit(digit: Spanned<&str>, radix: u32) -> Diagnostic {
+    d! {
+        format!("Invalid digit `{}` for a base {radix} number", *digit),
+        [Hint::new_error("", digit.1)],
+    }
+}
+
+pub fn missing_digits(number: Spanned<&str>) -> Diagnostic {
+    d! {
+        format!("Number `{}` has no digits", *number),
+        [Hint::new_error("", number.1)],
+    }
+}
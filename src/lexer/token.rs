@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{borrow::Cow, fmt::Display};
 
 use super::BracketType;
 
@@ -7,7 +7,10 @@ pub enum Token<'a> {
     OpenBracket(BracketType),
     CloseBracket(BracketType),
     Identifier(&'a str),
+    Number(Number<'a>),
     StringLiteral(String),
+    CharLiteral(char),
+    ByteStringLiteral(Vec<u8>),
     Plus,
     Minus,
     Asterisk,
@@ -15,6 +18,7 @@ pub enum Token<'a> {
     Greater,
     Less,
     Dot,
+    DotDot,
     Comma,
     Colon,
     Semicolon,
@@ -31,6 +35,38 @@ pub enum Token<'a> {
     HashTag,
 }
 
+/// A numeric literal. Eg `1_000`, `0xFFi64` or `1.5e3`
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Number<'a> {
+    /// The digits of the number without the radix prefix or suffix. This may contain `_`
+    /// separators, and for floats, a `.` and an exponent.
+    pub digits: &'a str,
+    /// The radix of the number (2, 8, 10, or 16)
+    pub radix: u32,
+    /// The type suffix (eg `i64`) if present
+    pub suffix: Option<&'a str>,
+    /// Whether the number has a fractional part or an exponent
+    pub is_float: bool,
+}
+
+impl Display for Number<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let prefix = match self.radix {
+            2 => "0b",
+            8 => "0o",
+            16 => "0x",
+            _ => "",
+        };
+
+        write!(
+            f,
+            "{prefix}{}{}",
+            self.digits,
+            self.suffix.unwrap_or_default()
+        )
+    }
+}
+
 /// Shorthand macro for `Token` literals.
 #[macro_export]
 macro_rules! T {
@@ -55,6 +91,9 @@ macro_rules! T {
     (".") => {
         $crate::lexer::Token::Dot
     };
+    ("..") => {
+        $crate::lexer::Token::DotDot
+    };
     (",") => {
         $crate::lexer::Token::Comma
     };
@@ -121,8 +160,11 @@ macro_rules! T {
 }
 
 impl<'a> Token<'a> {
-    pub fn as_str(&self) -> &str {
-        match self {
+    pub fn as_str(&self) -> Cow<'_, str> {
+        Cow::Borrowed(match self {
+            Token::CharLiteral(char) => return Cow::Owned(char.to_string()),
+            Token::Number(number) => return Cow::Owned(number.to_string()),
+            Token::ByteStringLiteral(lit) => return String::from_utf8_lossy(lit),
             Token::Identifier(ident) => ident,
             Token::StringLiteral(lit) => lit,
             T!("+") => "+",
@@ -132,6 +174,7 @@ impl<'a> Token<'a> {
             T!(">") => ">",
             T!("<") => "<",
             T!(".") => ".",
+            T!("..") => "..",
             T!(",") => ",",
             T!("(") => "(",
             T!(")") => ")",
@@ -152,7 +195,7 @@ impl<'a> Token<'a> {
             T!("<=") => "<=",
             T!("::") => "::",
             T!("#") => "#",
-        }
+        })
     }
 }
 
@@ -0,0 +1,158 @@
+use std::{
+    collections::HashSet,
+    fmt::Write as _,
+    process::{Command, Stdio},
+};
+
+use crate::cmdline::{CrateType, Emit, Parameters};
+
+/// Combines the object files of every crate into the final executable or library.
+/// Freestanding programs are linked with `ld` directly, while hosted programs are linked with
+/// `cc` so that libc and its startup files are included.
+///
+/// `crate_libraries` contains the name of every crate along with the libraries that it requested
+/// with `#![link(name = "...")]`.
+pub fn link(
+    params: &Parameters,
+    objects: &[String],
+    crate_libraries: &[(&str, &[String])],
+    has_main: bool,
+) -> Result<(), String> {
+    if params.emit == Some(Emit::Obj) {
+        return Ok(());
+    }
+
+    let linker = if params.hosted { "cc" } else { "ld" };
+
+    let (default_name, mut command) = match params.crate_type {
+        CrateType::Bin => {
+            if !has_main {
+                if params.emit == Some(Emit::Exe) {
+                    return Err("cannot emit an executable without a `main` function".into());
+                }
+
+                return Ok(());
+            }
+
+            ("a.out", Command::new(linker))
+        }
+        CrateType::Staticlib => {
+            let mut command = Command::new("ar");
+            command.arg("rcs");
+
+            ("liba.a", command)
+        }
+        CrateType::Cdylib => {
+            let mut command = Command::new(linker);
+            command.arg("-shared");
+
+            ("liba.so", command)
+        }
+    };
+
+    let output = params
+        .output
+        .clone()
+        .unwrap_or_else(|| format!("{}/{default_name}", params.out_dir));
+
+    if params.crate_type == CrateType::Staticlib {
+        // `ar` adds to existing archives, which could contain stale objects
+        let _ = std::fs::remove_file(&output);
+
+        command.arg(&output);
+    } else {
+        command.arg("-o").arg(&output);
+    }
+
+    command.args(objects);
+
+    let mut libraries: Vec<&str> = params.libraries.iter().map(String::as_str).collect();
+    for (_, crate_libraries) in crate_libraries {
+        libraries.extend(crate_libraries.iter().map(String::as_str));
+    }
+
+    // A library can be requested by several crates and the command line
+    let mut seen = HashSet::new();
+    libraries.retain(|library| seen.insert(*library));
+
+    // Archives are not linked, so they don't need the libraries
+    if params.crate_type != CrateType::Staticlib {
+        for path in &params.library_paths {
+            command.arg("-L").arg(path);
+        }
+
+        for library in &libraries {
+            command.arg(format!("-l{library}"));
+        }
+    }
+
+    let program = command.get_program().to_string_lossy().into_owned();
+
+    let result = command
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|err| format!("failed to run `{program}`: {err}"))?;
+
+    // Missing libraries are reported in the error along with what requested them, so the
+    // linker's own messages about them are left out
+    let mut missing = Vec::new();
+    for line in String::from_utf8_lossy(&result.stderr).lines() {
+        match missing_library(line, &libraries) {
+            Some(library) => missing.push(library),
+            None => eprintln!("{line}"),
+        }
+    }
+
+    if !result.status.success() {
+        let mut err = format!("`{program}` failed with {}", result.status);
+
+        for &library in libraries.iter().filter(|library| missing.contains(library)) {
+            let requested_by: Vec<String> = crate_libraries
+                .iter()
+                .filter(|(_, libraries)| libraries.iter().any(|l| l == library))
+                .map(|(crate_name, _)| format!("crate `{crate_name}`"))
+                .chain(
+                    params
+                        .libraries
+                        .iter()
+                        .any(|l| l == library)
+                        .then(|| "the command line".to_owned()),
+                )
+                .collect();
+
+            write!(
+                err,
+                "\ncould not find library `{library}` (requested by {})",
+                requested_by.join(", ")
+            )
+            .unwrap();
+        }
+
+        if !missing.is_empty() && !params.library_paths.is_empty() {
+            let paths: Vec<String> = params
+                .library_paths
+                .iter()
+                .map(|path| format!("`{path}`"))
+                .collect();
+
+            write!(err, "\nadditional library paths: {}", paths.join(", ")).unwrap();
+        }
+
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+/// Gets the library that a line of linker output reports as missing, if any
+fn missing_library<'a>(line: &str, libraries: &[&'a str]) -> Option<&'a str> {
+    if !line.contains("cannot find") && !line.contains("unable to find") {
+        return None;
+    }
+
+    line.split(|c: char| c.is_whitespace() || c == ':')
+        .find_map(|word| {
+            let library = word.strip_prefix("-l")?;
+            libraries.iter().copied().find(|&l| l == library)
+        })
+}
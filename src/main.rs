@@ -32,13 +32,11 @@ mod error_handling;
 mod util;
 
 mod cmdline;
+mod link;
 mod parser;
 
 /* TODO list
  *  - Allow functions and structs inside of code blocks
- *  - Structs
- *       - Add visibility
- *       - Give errors for recursively-defined types
  *  - Give parser access to source code to further reduce allocations
  *  - Use more efficient representation of ast::Path
  *  - Add more efficient conversion from byte position to line number
@@ -64,7 +62,15 @@ fn main() {
         process::exit(1)
     }
 
-    let do_codegen_phase = params.generate_ir || params.generate_asm || params.generate_object;
+    if params.emit == Some(cmdline::Emit::Exe) && params.crate_type != cmdline::CrateType::Bin {
+        eprintln!("wlab: `--emit=exe` can only be used with `--crate-type=bin`");
+        process::exit(1)
+    }
+
+    let do_codegen_phase = params.generate_ir
+        || params.generate_asm
+        || params.generate_object
+        || params.print_type_sizes.is_some();
     let do_parse_phase = do_codegen_phase || params.generate_ast;
     let do_lex_phase = do_parse_phase || params.lex_files;
 
@@ -149,7 +155,27 @@ fn main() {
 
     for (source, ast, crate_) in &crates {
         codegen_context
-            .add_types_and_functions(ast, source, crate_)
+            .add_types(ast, source, crate_)
+            .unwrap_or_else(|err| {
+                eprintln!("\n{}", err.render(source));
+                process::exit(1);
+            });
+    }
+
+    codegen_context
+        .generate_struct_bodies()
+        .unwrap_or_else(|(file_no, err)| {
+            eprintln!("\n{}", err.render(crates[file_no].0));
+            process::exit(1);
+        });
+
+    if let Some(format) = params.print_type_sizes {
+        print!("{}", codegen_context.type_sizes(format));
+    }
+
+    for (source, ast, crate_) in &crates {
+        codegen_context
+            .add_functions(ast, crate_)
             .unwrap_or_else(|err| {
                 eprintln!("\n{}", err.render(source));
                 process::exit(1);
@@ -169,4 +195,27 @@ fn main() {
     for &(file_no, ref warning) in warnings.iter() {
         eprintln!("\n{}", warning.render(&crates[file_no].0));
     }
+
+    if params.generate_object {
+        let objects: Vec<String> = crates
+            .iter()
+            .map(|(_, _, crate_)| format!("{}/{}.o", params.out_dir, crate_.name))
+            .collect();
+
+        let crate_libraries: Vec<(&str, &[String])> = crates
+            .iter()
+            .map(|(_, _, crate_)| (&*crate_.name, &*crate_.libraries))
+            .collect();
+
+        link::link(
+            &params,
+            &objects,
+            &crate_libraries,
+            codegen_context.has_main(),
+        )
+        .unwrap_or_else(|err| {
+            eprintln!("\x1b[1;31mwlab error: \x1b[m{err}");
+            process::exit(1)
+        });
+    }
 }
@@ -27,13 +27,15 @@ pub fn parse_module<'src>(mut tokens: &TokenStream<'src>) -> Result<ast::Module<
 
     let mut functions = Vec::new();
     let mut structs = Vec::new();
+    let mut type_aliases = Vec::new();
 
     for statement in statements {
         let span = statement.1;
         match statement.0 {
             ast::Statement::Function(func) => functions.push(S(func, span)),
             ast::Statement::Struct(struct_) => structs.push(S(struct_, span)),
-            _ => return Err(error::expected_function_or_struct(span)),
+            ast::Statement::TypeAlias(alias) => type_aliases.push(S(alias, span)),
+            _ => return Err(error::expected_item(span)),
         }
     }
 
@@ -41,5 +43,6 @@ pub fn parse_module<'src>(mut tokens: &TokenStream<'src>) -> Result<ast::Module<
         attributes,
         functions,
         structs,
+        type_aliases,
     })
 }
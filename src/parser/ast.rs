@@ -2,8 +2,12 @@ use std::fmt::Display;
 
 use wutil::Span;
 
-use crate::{error_handling::Spanned as S, util::MaybeVec};
+use crate::{error_handling::Spanned as S, lexer::Number, util::MaybeVec};
 
+/// A path (eg `std::println`) or a type.
+///
+/// Builtin types that are not identifiers use special segments: `!` and `()` are stored as a
+/// single segment, and a slice `[T]` is stored as a `[]` segment followed by the path of `T`.
 pub type Path<'src> = MaybeVec<S<&'src str>>;
 
 #[derive(Debug, PartialEq, Eq)]
@@ -11,6 +15,7 @@ pub struct Module<'src> {
     pub attributes: Vec<S<Attribute<'src>>>,
     pub functions: Vec<S<Function<'src>>>,
     pub structs: Vec<S<Struct<'src>>>,
+    pub type_aliases: Vec<S<TypeAlias<'src>>>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -19,6 +24,19 @@ pub enum Attribute<'src> {
     Intrinsic(&'src str),
     NoMangle,
     Packed,
+    ReprC,
+    Align(u32),
+    /// `#[copy]`: values of the struct are copied instead of moved
+    Copy,
+    /// `#[drop(function)]`: the function that is called when a value of the struct is dropped
+    Drop(&'src str),
+    /// `#![link(name = "m")]`: a library that the program is linked against
+    Link(String),
+    /// `#[global_allocator(alloc)]` or `#[global_allocator(dealloc)]`: the function replaces the
+    /// default implementation of `std::alloc` or `std::dealloc`. With `default` (eg
+    /// `#[global_allocator(alloc, default)]`), the function is itself a default implementation that
+    /// other crates can replace.
+    GlobalAllocator(&'src str, bool),
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -34,7 +52,9 @@ pub struct Function<'src> {
     pub return_type: Option<S<Path<'src>>>,
     pub attributes: Vec<S<Attribute<'src>>>,
     pub visibility: Visibility,
-    pub body: S<CodeBlock<'src>>,
+    /// The body of the function or `None` for `extern` functions, which are defined outside of
+    /// wlang
+    pub body: Option<S<CodeBlock<'src>>>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -51,12 +71,16 @@ pub enum Statement<'src> {
     },
     Function(Function<'src>),
     Struct(Struct<'src>),
+    TypeAlias(TypeAlias<'src>),
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct StructField<'src> {
     pub name: &'src str,
     pub type_: S<Path<'src>>,
+    pub visibility: Visibility,
+    /// The value used when the field is omitted from a struct initializer
+    pub default: Option<S<Expression<'src>>>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -70,6 +94,14 @@ pub struct Struct<'src> {
     pub name: &'src str,
     pub fields: Vec<S<StructField<'src>>>,
     pub attributes: Vec<S<Attribute<'src>>>,
+    pub visibility: Visibility,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TypeAlias<'src> {
+    pub name: S<&'src str>,
+    pub type_: S<Path<'src>>,
+    pub visibility: Visibility,
 }
 
 impl<'src> TryFrom<Statement<'src>> for Function<'src> {
@@ -100,10 +132,17 @@ pub enum Expression<'src> {
     StructInitializer {
         name: S<Path<'src>>,
         fields: Vec<S<StructInitializerField<'src>>>,
+        /// The struct that the remaining fields are copied from (eg `..base`)
+        base: Option<Box<S<Self>>>,
     },
     FieldAccess(Box<S<Self>>, S<&'src str>),
+    /// An element of a slice eg `args[1]`
+    Index(Box<S<Self>>, Box<S<Self>>),
+    Cast(Box<S<Self>>, S<Path<'src>>),
     Break(Option<Box<S<Self>>>),
     Return(Option<Box<S<Self>>>),
+    /// An inline assembly expression eg `asm!("syscall", in("rax") 60i64, out("rax") ret)`
+    InlineAsm(InlineAsm<'src>),
 }
 
 impl<'src> From<Expression<'src>> for Statement<'src> {
@@ -118,10 +157,39 @@ pub struct CodeBlock<'src> {
     pub trailing_semicolon: Option<Span>,
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub struct InlineAsm<'src> {
+    /// The lines of assembly in AT&T syntax
+    pub template: Vec<S<String>>,
+    pub operands: Vec<AsmOperand<'src>>,
+    /// The registers (or `"memory"`) that are modified by the assembly without being outputs
+    pub clobbers: Vec<S<String>>,
+    /// Whether the assembly has side effects other than writing its outputs (`options(volatile)`)
+    pub volatile: bool,
+}
+
+/// A register operand of an inline assembly expression eg `in("rdi") fd`
+#[derive(Debug, PartialEq, Eq)]
+pub struct AsmOperand<'src> {
+    pub direction: AsmDirection,
+    pub register: S<String>,
+    /// The value of an input, or the place that an output is written to
+    pub expression: S<Expression<'src>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AsmDirection {
+    In,
+    Out,
+    InOut,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum Literal<'src> {
-    Number(&'src str),
+    Number(Number<'src>),
     String(String),
+    Char(char),
+    ByteString(Vec<u8>),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -140,6 +208,22 @@ pub enum OpCode {
     LessEqual,
 }
 
+impl OpCode {
+    /// Gets the name of the trait that would define this operator for a user type. `&&` and `||`
+    /// cannot be overloaded.
+    pub fn trait_name(self) -> Option<&'static str> {
+        Some(match self {
+            OpCode::Plus => "Add",
+            OpCode::Minus => "Sub",
+            OpCode::Asterisk => "Mul",
+            OpCode::Slash => "Div",
+            OpCode::Equal | OpCode::NotEqual => "Eq",
+            OpCode::Greater | OpCode::Less | OpCode::GreaterEqual | OpCode::LessEqual => "Ord",
+            OpCode::Or | OpCode::And => return None,
+        })
+    }
+}
+
 impl Display for OpCode {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let str = match self {
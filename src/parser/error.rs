@@ -59,9 +59,9 @@ pub fn unmatched_bracket(tok: Spanned<&Token>) -> Diagnostic {
         [Hint::new_error("", tok.1)],
     }
 }
-pub fn expected_function_or_struct(span: Span) -> Diagnostic {
+pub fn expected_item(span: Span) -> Diagnostic {
     d! {
-        "expected function or struct definition",
+        "expected function, struct, or type alias definition",
         [Hint::new_error("", span)],
     }
 }
@@ -77,6 +77,12 @@ pub fn expected_body(span: Span) -> Diagnostic {
         [Hint::new_error("", span)],
     }
 }
+pub fn extern_function_body(span: Span) -> Diagnostic {
+    d! {
+        "extern functions cannot have a body",
+        [Hint::new_error("", span)],
+    }
+}
 pub fn expected_fields(span: Span) -> Diagnostic {
     d! {
         "expected struct fields",
@@ -125,6 +131,30 @@ pub fn expected_token(span: Span, tokens: &[Token]) -> Diagnostic {
         [Hint::new_error("", span)],
     }
 }
+pub fn expected_asm_template(span: Span) -> Diagnostic {
+    d! {
+        "expected assembly string",
+        [Hint::new_error("", span)],
+    }
+}
+pub fn invalid_asm_argument(span: Span) -> Diagnostic {
+    d! {
+        "invalid assembly argument; expected an operand (eg `in(\"rdi\") x`), `clobber(...)`, or `options(...)`",
+        [Hint::new_error("", span)],
+    }
+}
+pub fn expected_asm_register(span: Span) -> Diagnostic {
+    d! {
+        "expected register name (eg `\"rcx\"`)",
+        [Hint::new_error("", span)],
+    }
+}
+pub fn unknown_asm_option(span: Span) -> Diagnostic {
+    d! {
+        "unknown assembly option; the only option is `volatile`",
+        [Hint::new_error("", span)],
+    }
+}
 pub fn mismatched_brackets(opening: Span, closing: Span) -> Diagnostic {
     d! {
         "mismatched brackets",
@@ -134,6 +164,16 @@ pub fn mismatched_brackets(opening: Span, closing: Span) -> Diagnostic {
         ],
     }
 }
+pub fn fields_after_base(base: Span, field: Span) -> Diagnostic {
+    d! {
+        "the base struct must be the last item in a struct initializer",
+        [
+            Hint::new_info("base struct here", base),
+            Hint::new_error("", field),
+        ],
+    }
+}
+
 pub fn unexpected_tokens(span: Span) -> Diagnostic {
     d! {
         "unexpected tokens",
@@ -1,6 +1,6 @@
 //! Contains rules for the parser. Note: inputs are assumed to not have mismatched/unclosed brackets (these checks should be done in advance).
 
-use wutil::Span;
+use wutil::{iter::IterExt as _, Span};
 
 use crate::{
     error_handling::{self, Diagnostic, Spanned as S},
@@ -16,6 +16,7 @@ use crate::{
 
 type PResult<T> = Result<T, Diagnostic>;
 
+mod asm;
 mod attributes;
 mod bracket_expr;
 mod control_flow;
@@ -65,8 +66,11 @@ fn try_parse_expr<'src>(tokens: &TokenStream<'src>) -> PResult<Option<Expression
                 &[(T!("*"), OpCode::Asterisk), (T!("/"), OpCode::Slash)],
             )
         },
+        |tokens| try_parse_cast(tokens),
         |tokens| struct_::try_parse_field_access(tokens),
+        |tokens| try_parse_index(tokens),
         |tokens| control_flow::try_parse_loop(tokens),
+        |tokens| asm::try_parse_asm(tokens),
         |tokens| function::try_parse_function_call(tokens),
         |tokens| struct_::try_parse_struct_initializer(tokens),
     ];
@@ -97,6 +101,7 @@ fn try_parse_statement_from_front<'a, 'src>(
                 .map(|(ex, r)| (Statement::from(ex), r)))
         },
         |tokens| struct_::try_parse_struct_from_front(tokens),
+        |tokens| types::try_parse_type_alias_from_front(tokens),
         |tokens| Ok(control_flow::try_parse_if_from_front(tokens)?.map(|(ex, r)| (ex.into(), r))),
         |tokens| {
             Ok(
@@ -137,14 +142,18 @@ fn try_parse_identifier<'src>(tokens: &TokenStream<'src>) -> Option<Expression<'
 /// A literal
 fn try_parse_literal<'src>(tokens: &TokenStream<'src>) -> Option<Expression<'src>> {
     match tokens {
-        [S(Token::Identifier(ident), _)] => {
-            if ident.chars().next().unwrap().is_ascii_digit() {
-                return Some(Expression::Literal(Literal::Number(ident)));
-            }
+        [S(Token::Number(number), _)] => {
+            return Some(Expression::Literal(Literal::Number(*number)));
         }
         [S(Token::StringLiteral(lit), _)] => {
             return Some(Expression::Literal(Literal::String(lit.clone())));
         }
+        [S(Token::CharLiteral(char), _)] => {
+            return Some(Expression::Literal(Literal::Char(*char)));
+        }
+        [S(Token::ByteStringLiteral(lit), _)] => {
+            return Some(Expression::Literal(Literal::ByteString(lit.clone())));
+        }
         _ => {}
     }
 
@@ -240,3 +249,54 @@ fn try_parse_binary_operator<'src>(
 
     Ok(None)
 }
+
+/// A type cast. Eg `foo as i64`
+fn try_parse_cast<'src>(tokens: &TokenStream<'src>) -> PResult<Option<Expression<'src>>> {
+    let Some(as_tok) = NonBracketedIter::new(tokens)
+        .rev()
+        .find(|t| ***t == T!("as"))
+    else {
+        return Ok(None);
+    };
+
+    let i = tokens.elem_offset(as_tok).unwrap();
+
+    let expr_tokens = &tokens[..i];
+    let expr = try_parse_expr(expr_tokens)?
+        .ok_or_else(|| error::expected_expression(as_tok.1.span_at()))?;
+    let expr = S(expr, error_handling::span_of(expr_tokens).unwrap());
+
+    let mut type_tokens = &tokens[i + 1..];
+    let type_ = types::try_parse_type_from_front(&mut type_tokens)?
+        .ok_or_else(|| error::expected_type(as_tok.1.span_after()))?;
+
+    if let Some(span) = error_handling::span_of(type_tokens) {
+        return Err(error::unexpected_tokens(span));
+    }
+
+    Ok(Some(Expression::Cast(Box::new(expr), type_)))
+}
+
+/// An index into a slice eg `args[1]`
+fn try_parse_index<'src>(tokens: &TokenStream<'src>) -> PResult<Option<Expression<'src>>> {
+    let mut nb_iter = NonBracketedIter::new(tokens);
+
+    let Some([S(T!("]"), _), open_bracket]) = nb_iter.by_ref().rev().collect_n() else {
+        return Ok(None);
+    };
+
+    let open_idx = tokens.elem_offset(open_bracket).unwrap();
+
+    let slice_tokens = &tokens[..open_idx];
+    let Some(slice) = try_parse_expr(slice_tokens)? else {
+        return Ok(None);
+    };
+    let slice = S(slice, error_handling::span_of(slice_tokens).unwrap());
+
+    let index_tokens = &tokens[open_idx + 1..tokens.len() - 1];
+    let index = try_parse_expr(index_tokens)?
+        .ok_or_else(|| error::expected_expression(open_bracket.1.span_after()))?;
+    let index = S(index, error_handling::span_of(index_tokens).unwrap());
+
+    Ok(Some(Expression::Index(Box::new(slice), Box::new(index))))
+}
@@ -0,0 +1,118 @@
+use wutil::iter::IterExt;
+
+use crate::{
+    error_handling::{self, Spanned as S},
+    lexer::Token,
+    parser::{
+        ast::{AsmDirection, AsmOperand, Expression, InlineAsm},
+        error,
+        rules::{try_parse_expr, PResult},
+        util::{NonBracketedIter, TokenSplit},
+        TokenStream,
+    },
+    T,
+};
+
+/// An inline assembly expression eg `asm!("syscall", in("rax") 60i64, in("rdi") 0i64, options(volatile))`
+pub fn try_parse_asm<'src>(tokens: &TokenStream<'src>) -> PResult<Option<Expression<'src>>> {
+    let mut nb_iter = NonBracketedIter::new(tokens);
+
+    let Some([S(T!("asm"), _), S(T!("!"), _), S(T!("("), _), close_paren]) = nb_iter.collect_n()
+    else {
+        return Ok(None);
+    };
+
+    let close_idx = tokens.elem_offset(close_paren).unwrap();
+
+    if let Some(span) = error_handling::span_of(&tokens[close_idx + 1..]) {
+        return Err(error::unexpected_tokens(span));
+    }
+
+    let mut asm = InlineAsm {
+        template: Vec::new(),
+        operands: Vec::new(),
+        clobbers: Vec::new(),
+        volatile: false,
+    };
+
+    for (arg, separator) in TokenSplit::new(&tokens[3..close_idx], |t| t == &T!(",")) {
+        let Some(arg_span) = error_handling::span_of(arg) else {
+            let Some(separator) = separator else {
+                break; // Ignore trailing comma
+            };
+
+            return Err(error::invalid_asm_argument(separator.1));
+        };
+
+        // The template strings come before every other argument
+        if let [S(Token::StringLiteral(line), span)] = arg {
+            if !asm.operands.is_empty() || !asm.clobbers.is_empty() || asm.volatile {
+                return Err(error::invalid_asm_argument(*span));
+            }
+
+            asm.template.push(S(line.clone(), *span));
+            continue;
+        }
+
+        if asm.template.is_empty() {
+            return Err(error::expected_asm_template(arg_span));
+        }
+
+        match arg {
+            [S(Token::Identifier(direction @ ("in" | "out" | "inout")), _), S(T!("("), _), S(Token::StringLiteral(register), register_span), S(T!(")"), close_span), expr @ ..] =>
+            {
+                let direction = match *direction {
+                    "in" => AsmDirection::In,
+                    "out" => AsmDirection::Out,
+                    _ => AsmDirection::InOut,
+                };
+
+                let expr = try_parse_expr(expr)?
+                    .ok_or_else(|| error::expected_expression(close_span.span_after()))?;
+
+                asm.operands.push(AsmOperand {
+                    direction,
+                    register: S(register.clone(), *register_span),
+                    expression: S(expr, error_handling::span_of(&arg[4..]).unwrap()),
+                });
+            }
+            [S(T!("clobber"), _), S(T!("("), _), registers @ .., S(T!(")"), _)] => {
+                for (register, separator) in TokenSplit::new(registers, |t| t == &T!(",")) {
+                    match register {
+                        [S(Token::StringLiteral(register), span)] => {
+                            asm.clobbers.push(S(register.clone(), *span));
+                        }
+                        [] if separator.is_none() => {}
+                        _ => {
+                            let span = error_handling::span_of(register)
+                                .unwrap_or_else(|| separator.unwrap().1);
+
+                            return Err(error::expected_asm_register(span));
+                        }
+                    }
+                }
+            }
+            [S(T!("options"), _), S(T!("("), _), options @ .., S(T!(")"), _)] => {
+                for (option, separator) in TokenSplit::new(options, |t| t == &T!(",")) {
+                    match option {
+                        [S(T!("volatile"), _)] => asm.volatile = true,
+                        [] if separator.is_none() => {}
+                        _ => {
+                            let span = error_handling::span_of(option)
+                                .unwrap_or_else(|| separator.unwrap().1);
+
+                            return Err(error::unknown_asm_option(span));
+                        }
+                    }
+                }
+            }
+            _ => return Err(error::invalid_asm_argument(arg_span)),
+        }
+    }
+
+    if asm.template.is_empty() {
+        return Err(error::expected_asm_template(close_paren.1.span_at()));
+    }
+
+    Ok(Some(Expression::InlineAsm(asm)))
+}
@@ -63,12 +63,40 @@ fn parse_attribute<'src>(tokens: &TokenStream<'src>) -> Option<PResult<S<Attribu
         match *tokens {
             [S(T!("no_mangle"), _)] => Attribute::NoMangle,
             [S(T!("packed"), _)] => Attribute::Packed,
+            [S(T!("copy"), _)] => Attribute::Copy,
+            [S(T!("repr"), _), S(T!("("), _), S(T!("C"), _), S(T!(")"), _)] => Attribute::ReprC,
+            [S(T!("align"), _), S(T!("("), _), S(Token::Number(number), number_span), S(T!(")"), _)] =>
+            {
+                let align = (number.suffix.is_none() && !number.is_float)
+                    .then(|| {
+                        u32::from_str_radix(&number.digits.replace('_', ""), number.radix).ok()
+                    })
+                    .flatten();
+
+                let Some(align) = align else {
+                    return Some(Err(error::invalid_attribute(number_span)));
+                };
+
+                Attribute::Align(align)
+            }
+            [S(T!("drop"), _), S(T!("("), _), S(Token::Identifier(function), _), S(T!(")"), _)] => {
+                Attribute::Drop(function)
+            }
             [S(T!("intrinsic"), _), S(T!("("), _), S(Token::Identifier(intrinsic), _), S(T!(")"), _)] => {
                 Attribute::Intrinsic(intrinsic)
             }
+            [S(T!("global_allocator"), _), S(T!("("), _), S(Token::Identifier(function), _), S(T!(")"), _)] => {
+                Attribute::GlobalAllocator(function, false)
+            }
+            [S(T!("global_allocator"), _), S(T!("("), _), S(Token::Identifier(function), _), S(T!(","), _), S(T!("default"), _), S(T!(")"), _)] => {
+                Attribute::GlobalAllocator(function, true)
+            }
             [S(T!("declare_crate"), _), S(T!("("), _), S(Token::Identifier(crate_name), _), S(T!(")"), _)] => {
                 Attribute::DeclareCrate(crate_name)
             }
+            [S(T!("link"), _), S(T!("("), _), S(T!("name"), _), S(T!("="), _), S(Token::StringLiteral(ref name), _), S(T!(")"), _)] => {
+                Attribute::Link(name.clone())
+            }
             _ => {
                 return Some(Err(error::invalid_attribute(error_handling::span_of(
                     tokens,
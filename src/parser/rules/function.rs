@@ -17,7 +17,7 @@ use crate::{
     T,
 };
 
-/// A function. Eg `fn foo() {let x = ten; x}`
+/// A function. Eg `fn foo() {let x = ten; x}` or `extern fn strlen(s: str) -> i64`
 pub fn try_parse_function_from_front<'a, 'src>(
     tokens: &'a TokenStream<'src>,
 ) -> PResult<Option<(Statement<'src>, &'a TokenStream<'src>)>> {
@@ -25,6 +25,7 @@ pub fn try_parse_function_from_front<'a, 'src>(
         tokens: {
             do_(|toks| try_parse_attributes_from_front(toks)?) @ attributes;
             token("pub") @ visibility;
+            token("extern") @ extern_;
 
             required {
                 token("fn");
@@ -43,11 +44,22 @@ pub fn try_parse_function_from_front<'a, 'src>(
                     return Err(error::expected_type(arrow.1.span_after()))
                 };
             ) @ ret_type;
+        } => |remaining| {
+            // `extern` functions are defined outside of wlang, so they don't have a body
+            let (body, remaining) = if extern_.is_some() {
+                if let Some(span) = error_handling::span_of(remaining) {
+                    return Err(error::extern_function_body(span));
+                }
+
+                (None, remaining)
+            } else {
+                let Some((body, remaining)) = try_parse_code_block_from_front(remaining)? else {
+                    return Err(error::expected_body(ret_type.map_or(right_paren.1, |(_, ret_ty)| ret_ty.1).span_after()));
+                };
+
+                (Some(body), remaining)
+            };
 
-            required(do_(|toks| try_parse_code_block_from_front(toks)?)) else {
-                return Err(error::expected_body(ret_type.map_or(right_paren.1, |(_, ret_ty)| ret_ty.1).span_after()));
-            } @ (body, remaining);
-        } => {
             let visibility = if visibility.is_some() {
                 Visibility::Public
             } else {
@@ -3,7 +3,7 @@ use crate::{
     lexer::Token,
     parser::{
         self,
-        ast::{Expression, Statement, Struct, StructField, StructInitializerField},
+        ast::{Expression, Statement, Struct, StructField, StructInitializerField, Visibility},
         error,
         macros::match_tokens,
         rules::{self, attributes, path::try_parse_path_from_front, PResult},
@@ -24,14 +24,14 @@ pub fn try_parse_struct_initializer<'src>(
                     BracketType::Curly: {
                         do_(|tokens| parse_struct_initializer_fields(tokens)?);
                     }
-                ) @ (_, fields, _);
+                ) @ (_, (fields, base), _);
             };
         } => |remaining_tokens| {
             if let Some(span) = error_handling::span_of(remaining_tokens) {
                 return Err(error::unexpected_tokens(span));
             }
 
-            Ok(Some(Expression::StructInitializer { name, fields }))
+            Ok(Some(Expression::StructInitializer { name, fields, base }))
         }
     }
 }
@@ -64,6 +64,7 @@ pub fn try_parse_struct_from_front<'a, 'src>(
             do_(|tokens| {
                 attributes::try_parse_attributes_from_front(tokens)?
             }) @ attributes;
+            token("pub") @ visibility;
 
             required {
                 token("struct");
@@ -76,17 +77,48 @@ pub fn try_parse_struct_from_front<'a, 'src>(
                 } @ (_, fields, _)
             }
         } => |remaining| {
-            Ok(Some((Statement::Struct(Struct {name, fields, attributes: attributes.unwrap_or_default()}), remaining)))
+            let visibility = if visibility.is_some() {
+                Visibility::Public
+            } else {
+                Visibility::Private
+            };
+
+            Ok(Some((Statement::Struct(Struct {name, fields, attributes: attributes.unwrap_or_default(), visibility}), remaining)))
         }
     }
 }
 
+/// Parses the fields of a struct initializer and the optional base struct (eg `x: 1, ..base`)
 fn parse_struct_initializer_fields<'src>(
     tokens: &TokenStream<'src>,
-) -> PResult<Vec<S<StructInitializerField<'src>>>> {
+) -> PResult<(
+    Vec<S<StructInitializerField<'src>>>,
+    Option<Box<S<Expression<'src>>>>,
+)> {
     let mut fields = Vec::new();
+    let mut base: Option<Box<S<Expression>>> = None;
 
     for (field_toks, separator) in TokenSplit::new(tokens, |t| t == &T!(",")) {
+        if let Some(base) = &base {
+            let Some(span) = error_handling::span_of(field_toks).or(separator.map(|s| s.1)) else {
+                break;
+            };
+
+            return Err(parser::error::fields_after_base(base.1, span));
+        }
+
+        if let [S(T!(".."), dots_span), base_toks @ ..] = field_toks {
+            let base_expr = rules::try_parse_expr(base_toks)?
+                .ok_or_else(|| parser::error::expected_expression(dots_span.span_after()))?;
+
+            base = Some(Box::new(S(
+                base_expr,
+                error_handling::span_of(base_toks).unwrap(),
+            )));
+
+            continue;
+        }
+
         let Some(field) = parse_struct_initializer_field(field_toks)? else {
             let Some(separator) = separator else {
                 break;
@@ -98,7 +130,7 @@ fn parse_struct_initializer_fields<'src>(
         fields.push(S(field, error_handling::span_of(field_toks).unwrap()));
     }
 
-    Ok(fields)
+    Ok((fields, base))
 }
 
 fn parse_struct_fields<'src>(tokens: &TokenStream<'src>) -> PResult<Vec<S<StructField<'src>>>> {
@@ -122,6 +154,14 @@ fn parse_struct_fields<'src>(tokens: &TokenStream<'src>) -> PResult<Vec<S<Struct
 fn parse_struct_initializer_field<'src>(
     tokens: &TokenStream<'src>,
 ) -> PResult<Option<StructInitializerField<'src>>> {
+    // Shorthand for `name: name`
+    if let [S(Token::Identifier(name), span)] = tokens {
+        return Ok(Some(StructInitializerField {
+            name: S(name, *span),
+            val: S(Expression::Identifier(name), *span),
+        }));
+    }
+
     match_tokens! {
         tokens: {
             required {
@@ -155,6 +195,8 @@ fn parse_struct_initializer_field<'src>(
 fn parse_struct_field<'src>(tokens: &TokenStream<'src>) -> PResult<Option<S<StructField<'src>>>> {
     match_tokens! {
         tokens: {
+            token("pub") @ visibility;
+
             required {
                 ident() else {
                     let Some(first_tok) = tokens.first() else {
@@ -174,11 +216,24 @@ fn parse_struct_field<'src>(tokens: &TokenStream<'src>) -> PResult<Option<S<Stru
                 return Err(parser::error::expected_type(colon.1.span_after()))
             } @ type_;
         } => |remaining| {
-            if let Some(span) = error_handling::span_of(remaining) {
-                return Err(parser::error::unexpected_tokens(span));
-            }
+            let default = match remaining {
+                [] => None,
+                [equal_sign @ S(T!("="), _), default_tokens @ ..] => {
+                    let default = rules::try_parse_expr(default_tokens)?
+                        .ok_or_else(|| parser::error::expected_expression(equal_sign.1.span_after()))?;
+
+                    Some(S(default, error_handling::span_of(default_tokens).unwrap()))
+                }
+                _ => return Err(parser::error::unexpected_tokens(error_handling::span_of(remaining).unwrap())),
+            };
+
+            let visibility = if visibility.is_some() {
+                Visibility::Public
+            } else {
+                Visibility::Private
+            };
 
-            Ok(Some(S(StructField {name, type_}, error_handling::span_of(tokens).unwrap())))
+            Ok(Some(S(StructField {name, type_, visibility, default}, error_handling::span_of(tokens).unwrap())))
         }
     }
 }
@@ -1,15 +1,63 @@
 use wutil::Span;
 
 use crate::{
-    error_handling::Spanned as S,
+    error_handling::{self, Spanned as S},
     lexer::Token,
-    parser::{self, ast, TokenStream},
+    parser::{
+        self,
+        ast::{self, Statement, TypeAlias, Visibility},
+        macros::match_tokens,
+        TokenStream,
+    },
     util::MaybeVec,
     T,
 };
 
 use super::PResult;
 
+/// A type alias. Eg `pub type Fd = i32`
+pub fn try_parse_type_alias_from_front<'a, 'src>(
+    tokens: &'a TokenStream<'src>,
+) -> PResult<Option<(Statement<'src>, &'a TokenStream<'src>)>> {
+    match_tokens! {
+        tokens: {
+            token("pub") @ visibility;
+
+            required {
+                token("type");
+                ident() @ (name, name_tok);
+
+                token("=") else {
+                    return Err(parser::error::expected_token(name_tok.1.span_after(), &[T!("=")]));
+                } @ equal_sign;
+
+                expect_(do_(|toks| try_parse_type_from_front(toks)?)) else {
+                    return Err(parser::error::expected_type(equal_sign.1.span_after()));
+                } @ type_;
+            };
+        } => |remaining| {
+            if let Some(span) = error_handling::span_of(remaining) {
+                return Err(parser::error::unexpected_tokens(span));
+            }
+
+            let visibility = if visibility.is_some() {
+                Visibility::Public
+            } else {
+                Visibility::Private
+            };
+
+            Ok(Some((
+                Statement::TypeAlias(TypeAlias {
+                    name: S(name, name_tok.1),
+                    type_,
+                    visibility,
+                }),
+                remaining,
+            )))
+        }
+    }
+}
+
 pub fn try_parse_type_from_front<'src>(
     tokens: &mut &TokenStream<'src>,
 ) -> PResult<Option<S<ast::Path<'src>>>> {
@@ -18,6 +66,32 @@ pub fn try_parse_type_from_front<'src>(
         return Ok(Some(S(MaybeVec::of(S("!", *span)), *span)));
     }
 
+    if let [S(T!("["), open_span), ..] = *tokens {
+        let mut element_tokens = &tokens[1..];
+
+        let Some(element) = try_parse_type_from_front(&mut element_tokens)? else {
+            return Err(parser::error::expected_type(open_span.span_after()));
+        };
+
+        let [S(T!("]"), close_span), rem @ ..] = element_tokens else {
+            return Err(parser::error::expected_token(
+                element.1.span_after(),
+                &[T!("]")],
+            ));
+        };
+
+        *tokens = rem;
+
+        let span = (open_span.start..close_span.end).into();
+        let mut path = MaybeVec::of(S("[]", span));
+
+        for &segment in element.iter() {
+            path.push(segment);
+        }
+
+        return Ok(Some(S(path, span)));
+    }
+
     if let [S(T!("("), s1), S(T!(")"), s2), rem @ ..] = *tokens {
         *tokens = rem;
         let span = (s1.start..s2.end).into();
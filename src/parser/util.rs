@@ -82,7 +82,10 @@ impl<'a, 'src> DoubleEndedIterator for NonBracketedIter<'a, 'src> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{lexer::Lexer, T};
+    use crate::{
+        lexer::{Lexer, Number},
+        T,
+    };
 
     use super::*;
 
@@ -96,13 +99,22 @@ mod tests {
                 panic!("{}", err.render(src));
             });
 
+        let number = |digits| {
+            Token::Number(Number {
+                digits,
+                radix: 10,
+                suffix: None,
+                is_float: false,
+            })
+        };
+
         let mut iter = NonBracketedIter::new(&tokens);
-        assert_eq!(&**iter.next().unwrap(), &T!("9"));
+        assert_eq!(&**iter.next().unwrap(), &number("9"));
         assert_eq!(&**iter.next().unwrap(), &T!("+"));
         assert_eq!(&**iter.next().unwrap(), &T!("("));
         assert_eq!(&**iter.next().unwrap(), &T!(")"));
         assert_eq!(&**iter.next().unwrap(), &T!("="));
-        assert_eq!(&**iter.next().unwrap(), &T!("21"));
+        assert_eq!(&**iter.next().unwrap(), &number("21"));
         assert_eq!(iter.next(), None);
     }
 }
@@ -1,6 +1,14 @@
 use std::marker::PhantomData;
 
-use llvm_sys::LLVMBasicBlock;
+use llvm_sys::{
+    core::{
+        LLVMGetBasicBlockParent, LLVMGetBasicBlockTerminator, LLVMGetNumSuccessors,
+        LLVMGetSuccessor,
+    },
+    LLVMBasicBlock,
+};
+
+use crate::value::FnValue;
 
 #[derive(Clone, Copy)]
 #[repr(transparent)]
@@ -20,4 +28,25 @@ impl<'ctx> BasicBlock<'ctx> {
     pub fn raw(&self) -> *mut LLVMBasicBlock {
         self.ptr
     }
+
+    /// Gets the function that contains this block
+    pub fn parent(&self) -> FnValue<'ctx> {
+        unsafe { FnValue::from_raw(LLVMGetBasicBlockParent(self.ptr)) }
+    }
+
+    /// Gets the blocks that the terminator of this block can branch to. This is empty if the block
+    /// does not have a terminator yet
+    pub fn successors(&self) -> Vec<BasicBlock<'ctx>> {
+        unsafe {
+            let terminator = LLVMGetBasicBlockTerminator(self.ptr);
+
+            if terminator.is_null() {
+                return Vec::new();
+            }
+
+            (0..LLVMGetNumSuccessors(terminator))
+                .map(|i| BasicBlock::from_raw(LLVMGetSuccessor(terminator, i)))
+                .collect()
+        }
+    }
 }
@@ -5,12 +5,14 @@ use std::{
 
 use llvm_sys::{
     core::{
-        LLVMBuildAdd, LLVMBuildAlloca, LLVMBuildAnd, LLVMBuildBr, LLVMBuildCall2, LLVMBuildCondBr,
-        LLVMBuildExtractValue, LLVMBuildGEP2, LLVMBuildICmp, LLVMBuildLoad2, LLVMBuildMul,
-        LLVMBuildNot, LLVMBuildOr, LLVMBuildPhi, LLVMBuildRet, LLVMBuildSDiv, LLVMBuildStore,
-        LLVMBuildSub, LLVMBuildUDiv, LLVMBuildUnreachable, LLVMBuildXor, LLVMBuildZExt,
-        LLVMCountStructElementTypes, LLVMDisposeBuilder, LLVMGetInsertBlock,
-        LLVMPositionBuilderAtEnd, LLVMSetCurrentDebugLocation2,
+        LLVMBuildAdd, LLVMBuildAlloca, LLVMBuildAnd, LLVMBuildArrayAlloca, LLVMBuildBr,
+        LLVMBuildCall2, LLVMBuildCondBr, LLVMBuildExtractValue, LLVMBuildGEP2, LLVMBuildICmp,
+        LLVMBuildInsertValue, LLVMBuildLoad2, LLVMBuildMul, LLVMBuildNot, LLVMBuildOr,
+        LLVMBuildPhi, LLVMBuildPtrToInt, LLVMBuildRet, LLVMBuildRetVoid, LLVMBuildSDiv,
+        LLVMBuildSExt, LLVMBuildStore, LLVMBuildSub, LLVMBuildTrunc, LLVMBuildUDiv,
+        LLVMBuildUnreachable, LLVMBuildXor, LLVMBuildZExt, LLVMCountStructElementTypes,
+        LLVMDisposeBuilder, LLVMGetFirstInstruction, LLVMGetInsertBlock, LLVMPositionBuilderAtEnd,
+        LLVMPositionBuilderBefore, LLVMSetCurrentDebugLocation2,
     },
     LLVMBuilder, LLVMValue,
 };
@@ -113,6 +115,24 @@ impl<'ctx> Builder<'ctx> {
         unsafe { PtrValue::from_raw(LLVMBuildAlloca(self.ptr, type_.raw(), name_ptr)) }
     }
 
+    /// Allocates space for `count` values of `type_` on the stack
+    pub fn build_array_alloca(
+        &self,
+        type_: Type<'ctx>,
+        count: IntValue<'ctx>,
+        name: &CStr,
+    ) -> PtrValue<'ctx> {
+        let name_ptr = name.as_ptr().cast::<c_char>();
+        unsafe {
+            PtrValue::from_raw(LLVMBuildArrayAlloca(
+                self.ptr,
+                type_.raw(),
+                count.raw(),
+                name_ptr,
+            ))
+        }
+    }
+
     pub fn build_icmp(
         &self,
         op: IntPredicate,
@@ -223,6 +243,54 @@ impl<'ctx> Builder<'ctx> {
         }
     }
 
+    pub fn build_sext(
+        &self,
+        val: IntValue<'ctx>,
+        target: IntType<'ctx>,
+        name: &CStr,
+    ) -> IntValue<'ctx> {
+        unsafe {
+            IntValue::from_raw(LLVMBuildSExt(
+                self.ptr,
+                val.raw(),
+                target.raw(),
+                name.as_ptr(),
+            ))
+        }
+    }
+
+    pub fn build_trunc(
+        &self,
+        val: IntValue<'ctx>,
+        target: IntType<'ctx>,
+        name: &CStr,
+    ) -> IntValue<'ctx> {
+        unsafe {
+            IntValue::from_raw(LLVMBuildTrunc(
+                self.ptr,
+                val.raw(),
+                target.raw(),
+                name.as_ptr(),
+            ))
+        }
+    }
+
+    pub fn build_ptr_to_int(
+        &self,
+        val: PtrValue<'ctx>,
+        target: IntType<'ctx>,
+        name: &CStr,
+    ) -> IntValue<'ctx> {
+        unsafe {
+            IntValue::from_raw(LLVMBuildPtrToInt(
+                self.ptr,
+                val.raw(),
+                target.raw(),
+                name.as_ptr(),
+            ))
+        }
+    }
+
     pub fn build_extract_value(
         &self,
         val: StructValue<'ctx>,
@@ -245,6 +313,24 @@ impl<'ctx> Builder<'ctx> {
         })
     }
 
+    pub fn build_insert_value(
+        &self,
+        agg: StructValue<'ctx>,
+        val: Value<'ctx>,
+        idx: u32,
+        name: &CStr,
+    ) -> StructValue<'ctx> {
+        unsafe {
+            StructValue::from_raw(LLVMBuildInsertValue(
+                self.ptr,
+                agg.raw(),
+                val.raw(),
+                idx,
+                name.as_ptr(),
+            ))
+        }
+    }
+
     pub fn build_gep(
         &self,
         type_: Type<'ctx>,
@@ -270,6 +356,10 @@ impl<'ctx> Builder<'ctx> {
         unsafe { PhiValue::from_raw(LLVMBuildPhi(self.ptr, type_.raw(), name.as_ptr())) }
     }
 
+    pub fn build_ret_void(&self) {
+        unsafe { LLVMBuildRetVoid(self.ptr) };
+    }
+
     pub fn build_ret(&self, val: Value<'ctx>) {
         unsafe { LLVMBuildRet(self.ptr, val.raw()) };
     }
@@ -282,6 +372,19 @@ impl<'ctx> Builder<'ctx> {
         unsafe { LLVMPositionBuilderAtEnd(self.ptr, block.raw()) }
     }
 
+    /// Positions the builder before the first instruction of `block`
+    pub fn position_at_start(&self, block: BasicBlock<'ctx>) {
+        unsafe {
+            let first = LLVMGetFirstInstruction(block.raw());
+
+            if first.is_null() {
+                LLVMPositionBuilderAtEnd(self.ptr, block.raw())
+            } else {
+                LLVMPositionBuilderBefore(self.ptr, first)
+            }
+        }
+    }
+
     pub fn current_block(&self) -> Option<BasicBlock<'ctx>> {
         unsafe {
             let raw = LLVMGetInsertBlock(self.ptr);
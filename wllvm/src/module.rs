@@ -8,7 +8,7 @@ use llvm_sys::{
     analysis::LLVMVerifyModule,
     core::{
         LLVMAddFunction, LLVMAddGlobal, LLVMDisposeModule, LLVMGetNamedFunction,
-        LLVMPrintModuleToFile, LLVMPrintModuleToString,
+        LLVMGetNamedGlobal, LLVMPrintModuleToFile, LLVMPrintModuleToString,
     },
     target_machine::{
         LLVMCodeGenFileType, LLVMTargetMachineEmitToFile, LLVMTargetMachineEmitToMemoryBuffer,
@@ -85,6 +85,24 @@ impl<'ctx> Module<'ctx> {
         }
     }
 
+    /// Gets a global variable by its name
+    /// NOTE: `name` currently cannot contain any null bytes
+    pub fn get_global(&self, name: &(impl ?Sized + AsRef<[u8]>)) -> Option<GlobalVariable<'ctx>> {
+        let name = util::get_cstr_of(name.as_ref()).unwrap();
+
+        unsafe {
+            let raw = LLVMGetNamedGlobal(self.ptr, name.as_ptr().cast::<c_char>());
+
+            util::recycle_cstr(name);
+
+            if raw.is_null() {
+                return None;
+            }
+
+            Some(GlobalVariable::from_raw(raw))
+        }
+    }
+
     pub fn compile_to_buffer(
         &self,
         target: &TargetMachine,
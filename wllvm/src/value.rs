@@ -10,10 +10,11 @@ use std::{
 use llvm_sys::{
     core::{
         LLVMAddAttributeAtIndex, LLVMAddIncoming, LLVMAppendBasicBlockInContext, LLVMCountIncoming,
-        LLVMCountParams, LLVMGetLinkage, LLVMGetParam, LLVMGetTypeContext, LLVMGetValueKind,
+        LLVMCountParams, LLVMGetEntryBasicBlock, LLVMGetFirstBasicBlock, LLVMGetLinkage,
+        LLVMGetNextBasicBlock, LLVMGetParam, LLVMGetTypeContext, LLVMGetValueKind,
         LLVMGetValueName2, LLVMGlobalGetValueType, LLVMIsDeclaration, LLVMIsGlobalConstant,
-        LLVMPrintValueToString, LLVMSetGlobalConstant, LLVMSetInitializer, LLVMSetLinkage,
-        LLVMSetValueName2, LLVMTypeOf,
+        LLVMPrintValueToString, LLVMSetAlignment, LLVMSetGlobalConstant, LLVMSetInitializer,
+        LLVMSetLinkage, LLVMSetValueName2, LLVMTypeOf,
     },
     debuginfo::LLVMSetSubprogram,
     prelude::LLVMBool,
@@ -74,6 +75,11 @@ impl<'ctx> Value<'ctx> {
         unsafe { Type::from_raw(LLVMTypeOf(self.ptr)) }
     }
 
+    /// Sets the alignment (in bytes) of an `alloca`, `load`, `store` or global value
+    pub fn set_alignment(&self, bytes: u32) {
+        unsafe { LLVMSetAlignment(self.ptr, bytes) }
+    }
+
     pub fn kind(&self) -> LLVMValueKind {
         unsafe { LLVMGetValueKind(self.ptr) }
     }
@@ -137,10 +143,41 @@ impl<'ctx> FnValue<'ctx> {
         unsafe { LLVMAddAttributeAtIndex(self.ptr, u32::MAX, attr.raw()) }
     }
 
+    /// Adds an attribute to the parameter at index `idx`
+    pub fn add_param_attribute(&self, idx: u32, attr: Attribute<'ctx>) {
+        unsafe { LLVMAddAttributeAtIndex(self.ptr, idx + 1, attr.raw()) }
+    }
+
+    /// Adds an attribute to the return value
+    pub fn add_ret_attribute(&self, attr: Attribute<'ctx>) {
+        unsafe { LLVMAddAttributeAtIndex(self.ptr, 0, attr.raw()) }
+    }
+
     pub fn num_params(&self) -> u32 {
         unsafe { LLVMCountParams(self.ptr) }
     }
 
+    /// Gets the block that is executed first when the function is called
+    pub fn entry_block(&self) -> BasicBlock<'ctx> {
+        unsafe { BasicBlock::from_raw(LLVMGetEntryBasicBlock(self.ptr)) }
+    }
+
+    /// Gets the basic blocks of the function in order
+    pub fn basic_blocks(&self) -> Vec<BasicBlock<'ctx>> {
+        let mut blocks = Vec::new();
+
+        unsafe {
+            let mut block = LLVMGetFirstBasicBlock(self.ptr);
+
+            while !block.is_null() {
+                blocks.push(BasicBlock::from_raw(block));
+                block = LLVMGetNextBasicBlock(block);
+            }
+        }
+
+        blocks
+    }
+
     pub fn param(&self, idx: u32) -> Option<Value<'ctx>> {
         (idx < self.num_params()).then(|| unsafe { Value::from_raw(LLVMGetParam(self.ptr, idx)) })
     }
@@ -79,14 +79,27 @@ fn run_test(compiler: &Path, test: &parse::Test) -> Result<bool, TestError> {
         return Ok(true);
     }
 
-    link_test(&test.name)?;
+    if !test.artifacts.is_empty() {
+        for artifact in &test.artifacts {
+            if !artifact.try_exists()? {
+                eprintln!(
+                    "\x1b[1;31mwtool test: test `\x1b[m{}\x1b[1;31m` failed:\x1b[m `{}` was not produced",
+                    &test.name,
+                    artifact.display()
+                );
+                return Ok(false);
+            }
+        }
+
+        return Ok(true);
+    }
 
-    let mut test_process = Command::new("./compiler_output/a.out")
+    let (mut test_process, exit_status) = Command::new("./compiler_output/a.out")
         .stdout(Stdio::piped())
         .spawn()
         .and_then(|mut test| {
-            test.wait()?;
-            Ok(test)
+            let exit_status = test.wait()?;
+            Ok((test, exit_status))
         })
         .map_err(|err| {
             TestError::prefixed(
@@ -119,6 +132,14 @@ fn run_test(compiler: &Path, test: &parse::Test) -> Result<bool, TestError> {
         }
     }
 
+    if exit_status.code() != Some(test.exit_code) {
+        eprintln!(
+            "\x1b[1;31mwtool test: test `\x1b[m{}\x1b[1;31m` failed:\x1b[m expected exit code {}, got {exit_status}",
+            &test.name, test.exit_code
+        );
+        return Ok(false);
+    }
+
     Ok(true)
 }
 
@@ -127,10 +148,13 @@ fn compile_test(test: &parse::Test, compiler: &Path) -> Result<bool, TestError>
 
     eprintln!("\x1b[1mwtool:\x1b[m running test `{}`", &test.name);
 
-    let mut compiler = Command::new(compiler)
+    // The compiler links the program unless it is told to only emit object files
+    let compiler = Command::new(compiler)
         .args(&test.args)
+        .args(test.dont_link.then_some("--emit=obj"))
         .arg("--")
         .args(&test.sources)
+        .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()
         .map_err(|err| {
@@ -143,14 +167,11 @@ fn compile_test(test: &parse::Test, compiler: &Path) -> Result<bool, TestError>
             )
         })?;
 
-    let exit_status = compiler.wait().unwrap();
-    let mut compiler_stderr = Vec::new();
-    compiler
-        .stderr
-        .take()
-        .unwrap()
-        .read_to_end(&mut compiler_stderr)
-        .unwrap();
+    let output = compiler.wait_with_output().unwrap();
+    let exit_status = output.status;
+    let compiler_stdout = output.stdout;
+    let compiler_stderr = output.stderr;
+
     if test.should_fail {
         if exit_status.success() {
             eprintln!("\x1b[1;31mwtool test: test `\x1b[m{}\x1b[1;31m` failed:\x1b[m compiler did not fail", &test.name);
@@ -184,6 +205,21 @@ fn compile_test(test: &parse::Test, compiler: &Path) -> Result<bool, TestError>
             return Ok(false);
         }
     }
+    if let Some(expected_stdout) = &test.compiler_stdout {
+        if expected_stdout != &compiler_stdout {
+            eprintln!(
+                "\x1b[1;31mwtool test: test `\x1b[m{}\x1b[1;31m` failed:\x1b[m incorrect compiler stdout",
+                &test.name
+            );
+            eprintln!(
+                "\nExpected compiler stdout:\n------------------------------------------\n{}\n------------------------------------------", expected_stdout.escape_ascii()
+            );
+            eprintln!(
+                "\nGot compiler stdout:\n------------------------------------------\n{}\n------------------------------------------", compiler_stdout.escape_ascii()
+            );
+            return Ok(false);
+        }
+    }
 
     Ok(true)
 }
@@ -195,20 +231,3 @@ fn clean() -> Result<(), TestError> {
         .map_err(|err| TestError::prefixed(err, "failed to clean `compiler_output`: "))?;
     Ok(())
 }
-
-fn link_test(test: &str) -> Result<(), TestError> {
-    let linker_result = Command::new("./link.sh")
-        .spawn()
-        .and_then(|mut c| c.wait())
-        .map_err(|err| {
-            TestError::prefixed(err, &format!("failed to run linker for test `{test}`: "))
-        })?;
-
-    if !linker_result.success() {
-        return Err(TestError::new(format!(
-            "linker failed to run for test {test}"
-        )));
-    }
-
-    Ok(())
-}
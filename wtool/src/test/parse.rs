@@ -16,12 +16,25 @@ pub struct Test {
     #[serde(skip)]
     pub stderr: Option<Vec<u8>>,
 
+    /// The expected stdout of the compiler itself (eg for `--print-type-sizes`)
+    #[serde(skip)]
+    pub compiler_stdout: Option<Vec<u8>>,
+
     #[serde(default)]
     pub should_fail: bool,
 
     #[serde(default)]
     pub dont_link: bool,
 
+    /// The exit code that the test program is expected to return
+    #[serde(default)]
+    pub exit_code: i32,
+
+    /// Files that compiling the test must produce, relative to the repository. Tests that list
+    /// artifacts are not run
+    #[serde(default)]
+    pub artifacts: Vec<PathBuf>,
+
     #[serde(default = "default_sources")]
     pub sources: Vec<PathBuf>,
 
@@ -53,6 +66,10 @@ pub fn parse_test(path: &mut PathBuf) -> Result<Test, TestError> {
     test.stderr = read_file(&path)?;
     path.pop();
 
+    path.push("compiler_stdout");
+    test.compiler_stdout = read_file(path)?;
+    path.pop();
+
     // Get full path to file sources //
     {
         let mut tmp_buf = PathBuf::new();